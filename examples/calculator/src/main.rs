@@ -6,7 +6,7 @@ use simple_tui::{
         Event,
     },
     ui::{
-        style::{StyleSheet, Style, Length, Padding},
+        style::{StyleSheet, Style, Length, Margin, Padding, TextAlign, VerticalAlign},
         screen::Screen,
         widget::{Widgets, text::Text, container::{Container, ContainerKind}, button::Button}
     },
@@ -123,24 +123,26 @@ fn build_ui() -> Container {
                         StyleSheet {
                             width: Length::Relative(1.0),
                             height: Length::Relative(1.0),
-                            padding: Padding {
-                                x_start: 1.0,
-                                x_end: 1.0,
-                                y_start: 2.0,
-                                y_end: 2.0,
-                            }
+                            margin: Margin {
+                                x_start: 1,
+                                x_end: 1,
+                                y_start: 1,
+                                y_end: 1,
+                            },
+                            ..Default::default()
                         }
                     ))
                 ],
                 StyleSheet {
                     width: Length::Relative(1.0),
                     height: Length::Absolute(5.0),
-                    padding: Padding {
-                        x_start: 1.0,
-                        x_end: 1.0,
-                        y_start: 1.0,
-                        y_end: 0.0,
-                    }
+                    margin: Margin {
+                        x_start: 1,
+                        x_end: 1,
+                        y_start: 1,
+                        y_end: 0,
+                    },
+                    ..Default::default()
                 }
             ).with_default_borders()),
             build_number_row(1),
@@ -187,12 +189,15 @@ fn get_button_style(num: u8) -> StyleSheet {
     StyleSheet {
         width: Length::Relative(1.0 / (4.0 - num as f32)),
         height: Length::Relative(1.0),
+        text_align: TextAlign::Center,
+        vertical_align: VerticalAlign::Middle,
         padding: Padding {
-            x_start: 0.0,
-            x_end: 0.0,
-            y_start: 0.0,
-            y_end: 0.0
-        }
+            x_start: 0,
+            x_end: 0,
+            y_start: 0,
+            y_end: 0
+        },
+        ..Default::default()
     }
 }
 
@@ -201,10 +206,11 @@ fn get_button_cont_style(num: u8) -> StyleSheet {
         width: Length::Relative(1.0),
         height: Length::Relative(1.0 / (4.0 - num as f32)),
         padding: Padding {
-            x_start: 0.0,
-            x_end: 0.0,
-            y_start: 0.0,
-            y_end: 0.0
-        }
+            x_start: 0,
+            x_end: 0,
+            y_start: 0,
+            y_end: 0
+        },
+        ..Default::default()
     }
 }
\ No newline at end of file
@@ -1,14 +1,15 @@
-use std::{time::Duration};
+use std::{env, fs, time::Duration};
 
 use simple_tui::{
     message::Message,
     events::{
-        Event,
+        Event, KeyCode, KeyMod,
     },
     ui::{
-        style::{StyleSheet, Style, Length},
+        border::Border,
+        style::{StyleSheet, Length, TextAlign},
         screen::Screen,
-        widget::{Widgets, container::{Container, ContainerKind}, input::Input, button::Button}
+        widget::{Widget, Widgets, container::{Container, ContainerKind}, text_area::TextArea}
     },
     application::Application,
 };
@@ -19,38 +20,69 @@ fn main() {
     application.run();
 }
 
-struct State<'a> {
-    screen: Screen<'a>,
+struct State {
+    screen: Screen,
 
-    container: Container<'a>,
+    container: Container,
 
+    path: Option<String>,
+    text: String,
+    modified: bool,
     exit: bool,
 }
-impl<'a> Application for State<'a> {
+impl State {
+    fn save(&mut self) {
+        if let Some(path) = &self.path {
+            if fs::write(path, &self.text).is_ok() {
+                self.modified = false;
+            }
+        }
+    }
+
+    fn title(&self) -> String {
+        let name = self.path.clone().unwrap_or_else(|| String::from("[no file]"));
+        if self.modified {
+            format!(" {} [+] ", name)
+        } else {
+            format!(" {} ", name)
+        }
+    }
+}
+impl Application for State {
     fn new() -> Self {
-        let ui_container = build_ui();
+        // the file given as first argument is opened
+        let path = env::args().nth(1);
+        let text = path
+            .as_ref()
+            .and_then(|path| fs::read_to_string(path).ok())
+            .unwrap_or_default();
+
+        let ui_container = build_ui(&text);
         let screen = Screen::new(ui_container).unwrap();
         let container = screen.container.clone();
         Self {
             screen,
 
-            container: container,
+            container,
 
+            path,
+            text,
+            modified: false,
             exit: false,
         }
     }
 
     fn update(&mut self, msg: Vec<String>) {
         let msg = Message::from_strings(msg);
-        match msg.parameter[0].as_str() {
-            "exit" => self.exit = true,
-            _ => (),
+        if msg.parameter[0].as_str() == "text_change" {
+            self.text = msg.parameter[1].clone();
+            self.modified = true;
         }
     }
 
     fn view(&mut self) {
+        self.container.border.title = Some(self.title());
         self.screen.container = self.container.clone();
-        self.screen.container.apply_style();
     }
 
     fn run(&mut self) {
@@ -60,80 +92,48 @@ impl<'a> Application for State<'a> {
             self.screen.draw();
             self.screen.render().unwrap();
 
-            if let Some(e) = Event::read_non_blocking(Duration::from_secs(1)) {
-                let cont = self.screen.handle_event(e.clone());
-                self.container = cont.clone();
-                let msg = self.screen.get_message();
-                if let Some(msg) = msg {
-                    self.update(msg);
+            match Event::read_non_blocking(Duration::from_secs(1)) {
+                Some(Event::Key(KeyMod::Control, KeyCode::Char('s'))) => self.save(),
+                Some(Event::Key(_, KeyCode::Esc)) => self.exit = true,
+                Some(e) => {
+                    let cont = self.screen.handle_event(e.clone());
+                    self.container = cont.clone();
+                    while let Some(msg) = self.screen.get_message() {
+                        self.update(msg);
+                    }
                 }
+                None => (),
             }
         }
         self.screen.exit().unwrap();
     }
 }
 
-fn build_ui<'a>() -> Container<'a> {
-    let main_container = Container::new(
-        ContainerKind::Column,
-        vec![
-            // exit button
-            Widgets::Button(Button::new(
-                "exit",
-                StyleSheet {
-                    width: Length::Absolute(6.0),
-                    height: Length::Absolute(3.0),
-                    padding: (0.0, 0.0),
-                    ..Default::default()
-                }
-            ).on_click("exit")),
-
-            Widgets::Container(Container::new(
-                ContainerKind::Row,
-                vec![
-                    Widgets::Input(Input::new(
-                        "input: ",
-                        "input_change",
-                        StyleSheet {
-                            width: Length::Relative(0.33),
-                            height: Length::Relative(0.33),
-                            ..Default::default()
-                        }
-                    ).with_tab_blocker(true)),
-                    Widgets::Input(Input::new(
-                        "input: ",
-                        "input_change",
-                        StyleSheet {
-                            width: Length::Relative(0.5),
-                            height: Length::Relative(0.5),
-                            ..Default::default()
-                        }
-                    ).with_tab_blocker(true)),
-                    Widgets::Input(Input::new(
-                        "input: ",
-                        "input_change",
-                        StyleSheet {
-                            width: Length::Relative(1.0),
-                            height: Length::Relative(1.0),
-                            ..Default::default()
-                        }
-                    ).with_tab_blocker(true))
-                ],
-                StyleSheet {
-                    width: Length::Relative(1.0),
-                    height: Length::Relative(1.0),
-                    padding: (0.0, 0.0),
-                    ..Default::default()
-                }
-            )
-            .with_default_borders()
-            .with_tab_selector())
-        ],
-        StyleSheet::default(),
+fn build_ui(text: &str) -> Container {
+    let mut editor = TextArea::new(
+        "text_change",
+        StyleSheet {
+            width: Length::Relative(1.0),
+            height: Length::Relative(1.0),
+            ..Default::default()
+        }
     )
-    .with_default_borders()
-    .with_tab_selector()
-    .always_selected();
+    .with_text(text)
+    .with_line_numbers();
+    editor.select(true);
 
-    main_container
-}
\ No newline at end of file
+    Container::new(
+        ContainerKind::Column,
+        vec![Widgets::TextArea(editor)],
+        StyleSheet {
+            width: Length::Relative(1.0),
+            height: Length::Relative(1.0),
+            ..Default::default()
+        },
+    )
+    .with_border(
+        Border::rounded()
+            .with_footer(" Ctrl+S save | Esc exit ", TextAlign::Right)
+    )
+    .always_selected()
+}
@@ -0,0 +1,90 @@
+use std::io::{stdout, Write};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+
+// shared by all widgets, so text can be copied from one widget and pasted into another
+static CLIPBOARD: Mutex<String> = Mutex::new(String::new());
+static OSC52: AtomicBool = AtomicBool::new(false);
+
+// tests run in parallel, the ones using the clipboard take turns
+#[cfg(test)]
+static TEST_LOCK: Mutex<()> = Mutex::new(());
+
+#[cfg(test)]
+pub(crate) fn lock_for_test() -> std::sync::MutexGuard<'static, ()> {
+    TEST_LOCK.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+const BASE64: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// stores the text in the clipboard and also sends it to the terminal if OSC 52 is enabled
+pub fn copy(text: &str) {
+    if let Ok(mut clipboard) = CLIPBOARD.lock() {
+        *clipboard = String::from(text);
+    }
+
+    if OSC52.load(Ordering::Relaxed) {
+        let mut stdout = stdout();
+        // the terminal may ignore it, so errors are ignored as well
+        let _ = write!(stdout, "{}", osc52_sequence(text));
+        let _ = stdout.flush();
+    }
+}
+
+/// text that was copied last
+pub fn paste() -> String {
+    CLIPBOARD
+        .lock()
+        .map(|clipboard| clipboard.clone())
+        .unwrap_or_default()
+}
+
+/// copied text is also written into the system clipboard of the terminal with OSC 52 escape sequences,
+/// which works over ssh without X11 if the terminal supports it. pasting still uses the internal clipboard
+pub fn set_osc52(enabled: bool) {
+    OSC52.store(enabled, Ordering::Relaxed);
+}
+
+pub fn osc52_enabled() -> bool {
+    OSC52.load(Ordering::Relaxed)
+}
+
+fn osc52_sequence(text: &str) -> String {
+    format!("\x1b]52;c;{}\x07", base64(text.as_bytes()))
+}
+
+fn base64(bytes: &[u8]) -> String {
+    let mut encoded = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let b = [
+            chunk[0],
+            chunk.get(1).copied().unwrap_or(0),
+            chunk.get(2).copied().unwrap_or(0),
+        ];
+        let n = (b[0] as u32) << 16 | (b[1] as u32) << 8 | b[2] as u32;
+        for i in 0..4 {
+            // missing bytes are padded
+            if i <= chunk.len() {
+                encoded.push(BASE64[(n >> (18 - 6 * i) & 0x3f) as usize] as char);
+            } else {
+                encoded.push('=');
+            }
+        }
+    }
+    encoded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn base64_pads_the_last_chunk() {
+        assert_eq!(base64(b""), "");
+        assert_eq!(base64(b"f"), "Zg==");
+        assert_eq!(base64(b"fo"), "Zm8=");
+        assert_eq!(base64(b"foo"), "Zm9v");
+        assert_eq!(base64(b"foobar"), "Zm9vYmFy");
+        assert_eq!(osc52_sequence("hi"), "\x1b]52;c;aGk=\x07");
+    }
+}
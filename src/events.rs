@@ -106,7 +106,7 @@ impl Event {
     }
 }
 
-fn crossterm_keycode_converter<'a>(code: TermKeyCode) -> KeyCode {
+fn crossterm_keycode_converter(code: TermKeyCode) -> KeyCode {
     let code: KeyCode = match code {
         TermKeyCode::Backspace => KeyCode::Backspace,
         TermKeyCode::Enter => KeyCode::Enter,
@@ -116,7 +116,7 @@ fn crossterm_keycode_converter<'a>(code: TermKeyCode) -> KeyCode {
         TermKeyCode::Down => KeyCode::Down,
         TermKeyCode::Home => KeyCode::Home,
         TermKeyCode::End => KeyCode::End,
-        TermKeyCode::PageUp => KeyCode::PageDown,
+        TermKeyCode::PageUp => KeyCode::PageUp,
         TermKeyCode::PageDown => KeyCode::PageDown,
         TermKeyCode::Tab => KeyCode::Tab,
         TermKeyCode::BackTab => KeyCode::BackTab,
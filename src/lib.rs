@@ -1,18 +1,93 @@
 pub mod application;
+pub mod clipboard;
 pub mod events;
 pub mod message;
 pub mod ui;
 
-#[derive(Copy, Clone, Debug, PartialEq)]
+use ui::style::Spacing;
+
+/// size in terminal cells
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
 pub struct Size {
-    x: f32,
-    y: f32,
+    pub x: usize,
+    pub y: usize,
 }
-impl Eq for Size {}
 
-#[derive(Copy, Clone, Debug, PartialEq)]
+/// position in terminal cells
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
 pub struct Position {
-    x: f32,
-    y: f32,
+    pub x: usize,
+    pub y: usize,
+}
+
+/// rectangle of terminal cells
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Rect {
+    pub x: usize,
+    pub y: usize,
+    pub width: usize,
+    pub height: usize,
+}
+impl Rect {
+    pub fn new(position: Position, size: Size) -> Self {
+        Self {
+            x: position.x,
+            y: position.y,
+            width: size.x,
+            height: size.y,
+        }
+    }
+
+    pub fn position(&self) -> Position {
+        Position {
+            x: self.x,
+            y: self.y,
+        }
+    }
+
+    pub fn size(&self) -> Size {
+        Size {
+            x: self.width,
+            y: self.height,
+        }
+    }
+
+    /// first column right of the rectangle
+    pub fn right(&self) -> usize {
+        self.x + self.width
+    }
+
+    /// first row below the rectangle
+    pub fn bottom(&self) -> usize {
+        self.y + self.height
+    }
+
+    pub fn contains(&self, x: usize, y: usize) -> bool {
+        x >= self.x && x < self.right() && y >= self.y && y < self.bottom()
+    }
+
+    /// rectangle shrunk by the size of each side of `spacing`
+    pub fn inset(&self, spacing: Spacing) -> Rect {
+        Rect {
+            x: self.x + spacing.x_start,
+            y: self.y + spacing.y_start,
+            width: self.width.saturating_sub(spacing.horizontal()),
+            height: self.height.saturating_sub(spacing.vertical()),
+        }
+    }
+
+    /// overlapping area of both rectangles, empty when they do not overlap
+    pub fn intersection(&self, other: Rect) -> Rect {
+        let x = self.x.max(other.x);
+        let y = self.y.max(other.y);
+        let right = self.right().min(other.right()).max(x);
+        let bottom = self.bottom().min(other.bottom()).max(y);
+
+        Rect {
+            x,
+            y,
+            width: right - x,
+            height: bottom - y,
+        }
+    }
 }
-impl Eq for Position {}
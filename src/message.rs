@@ -7,6 +7,7 @@ impl Message {
     pub fn from_strings(strings: Vec<String>) -> Self {
         Self { parameter: strings }
     }
+    #[allow(clippy::result_unit_err)]
     pub fn get_value<T: FromStr>(&self, index: usize) -> Result<T, ()> {
         if self.parameter.len() > index {
             match self.parameter[index].parse::<T>() {
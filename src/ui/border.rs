@@ -0,0 +1,289 @@
+use super::cell::{Canvas, Cell};
+use super::style::{Attributes, Color, Spacing, TextAlign};
+use super::text_layout;
+use super::widget::TAB_WIDTH;
+
+/// symbols a border is drawn with
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum BorderType {
+    Plain,
+    Rounded,
+    Double,
+    Thick,
+    Ascii,
+    /// no border is drawn and it takes no space
+    None,
+    /// upper left, upper right, bottom right, bottom left, horizontal and vertical symbol
+    Custom([char; 6]),
+}
+impl BorderType {
+    /// upper left, upper right, bottom right, bottom left, horizontal and vertical symbol
+    pub fn symbols(&self) -> [char; 6] {
+        match self {
+            BorderType::Plain => ['┌', '┐', '┘', '└', '─', '│'],
+            BorderType::Rounded => ['╭', '╮', '╯', '╰', '─', '│'],
+            BorderType::Double => ['╔', '╗', '╝', '╚', '═', '║'],
+            BorderType::Thick => ['┏', '┓', '┛', '┗', '━', '┃'],
+            BorderType::Ascii => ['+', '+', '+', '+', '-', '|'],
+            BorderType::None => [' '; 6],
+            BorderType::Custom(symbols) => *symbols,
+        }
+    }
+}
+
+/// which sides of a border are drawn
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Sides {
+    pub top: bool,
+    pub bottom: bool,
+    pub left: bool,
+    pub right: bool,
+}
+impl Sides {
+    pub fn all() -> Self {
+        Self {
+            top: true,
+            bottom: true,
+            left: true,
+            right: true,
+        }
+    }
+
+    pub fn horizontal() -> Self {
+        Self {
+            top: true,
+            bottom: true,
+            left: false,
+            right: false,
+        }
+    }
+
+    pub fn vertical() -> Self {
+        Self {
+            top: false,
+            bottom: false,
+            left: true,
+            right: true,
+        }
+    }
+}
+impl Default for Sides {
+    fn default() -> Self {
+        Self::all()
+    }
+}
+
+/// border of a widget with an optional title embedded in the top edge and footer in the bottom edge
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Border {
+    pub kind: BorderType,
+    pub sides: Sides,
+    pub title: Option<String>,
+    pub title_align: TextAlign,
+    pub footer: Option<String>,
+    pub footer_align: TextAlign,
+}
+impl Border {
+    pub fn new(kind: BorderType) -> Self {
+        Self {
+            kind,
+            sides: Sides::all(),
+            title: None,
+            title_align: TextAlign::Left,
+            footer: None,
+            footer_align: TextAlign::Left,
+        }
+    }
+
+    pub fn plain() -> Self {
+        Self::new(BorderType::Plain)
+    }
+
+    pub fn rounded() -> Self {
+        Self::new(BorderType::Rounded)
+    }
+
+    pub fn double() -> Self {
+        Self::new(BorderType::Double)
+    }
+
+    pub fn thick() -> Self {
+        Self::new(BorderType::Thick)
+    }
+
+    pub fn ascii() -> Self {
+        Self::new(BorderType::Ascii)
+    }
+
+    pub fn none() -> Self {
+        Self::new(BorderType::None)
+    }
+
+    pub fn with_sides(mut self, sides: Sides) -> Self {
+        self.sides = sides;
+        self
+    }
+
+    pub fn with_title<S: Into<String>>(mut self, title: S, align: TextAlign) -> Self {
+        self.title = Some(title.into());
+        self.title_align = align;
+        self
+    }
+
+    pub fn with_footer<S: Into<String>>(mut self, footer: S, align: TextAlign) -> Self {
+        self.footer = Some(footer.into());
+        self.footer_align = align;
+        self
+    }
+
+    /// cells taken by each side
+    pub fn width(&self) -> Spacing {
+        if self.kind == BorderType::None {
+            return Spacing::default();
+        }
+        Spacing {
+            x_start: self.sides.left as usize,
+            x_end: self.sides.right as usize,
+            y_start: self.sides.top as usize,
+            y_end: self.sides.bottom as usize,
+        }
+    }
+
+    /// draws the border along the edges of the canvas with the symbols of `symbols`,
+    /// which may differ from `kind` while the widget is in a state. the cells keep their background
+    pub fn draw(
+        &self,
+        canvas: &mut Canvas,
+        symbols: BorderType,
+        color: Color,
+        attributes: Attributes,
+    ) {
+        let width = self.width();
+        let height = canvas.len();
+        if width == Spacing::default() || height == 0 || canvas[0].is_empty() {
+            return;
+        }
+        let symbols = symbols.symbols();
+        let (x_edge, y_edge) = (canvas[0].len() - 1, height - 1);
+
+        for (y, row) in canvas.iter_mut().enumerate() {
+            let top = y == 0 && self.sides.top;
+            let bottom = y == y_edge && self.sides.bottom;
+            for (x, cell) in row.iter_mut().enumerate() {
+                let left = x == 0 && self.sides.left;
+                let right = x == x_edge && self.sides.right;
+                let symbol = match (top, bottom, left, right) {
+                    (true, _, true, _) => symbols[0],
+                    (true, _, _, true) => symbols[1],
+                    (_, true, _, true) => symbols[2],
+                    (_, true, true, _) => symbols[3],
+                    (true, _, _, _) | (_, true, _, _) => symbols[4],
+                    (_, _, true, _) | (_, _, _, true) => symbols[5],
+                    _ => continue,
+                };
+                cell.symbol = symbol.to_string();
+                cell.fg = color;
+                cell.attributes = attributes;
+            }
+        }
+
+        // labels are drawn between the corners
+        let start = width.x_start;
+        let end = x_edge + 1 - width.x_end;
+        if let (Some(title), true) = (&self.title, self.sides.top) {
+            draw_label(&mut canvas[0][..], start, end, title, self.title_align);
+        }
+        if let (Some(footer), true) = (&self.footer, self.sides.bottom) {
+            draw_label(
+                &mut canvas[y_edge][..],
+                start,
+                end,
+                footer,
+                self.footer_align,
+            );
+        }
+    }
+}
+impl Default for Border {
+    fn default() -> Self {
+        Self::none()
+    }
+}
+
+// first line of the label, clipped to the space between `start` and `end`
+fn draw_label(row: &mut [Cell], start: usize, end: usize, label: &str, align: TextAlign) {
+    if end <= start {
+        return;
+    }
+    let graphemes = text_layout::split_lines(label, TAB_WIDTH)
+        .into_iter()
+        .next()
+        .unwrap_or_default();
+    let width = text_layout::line_width(&graphemes).min(end - start);
+    let x = match align {
+        TextAlign::Center => start + (end - start - width) / 2,
+        TextAlign::Right => end - width,
+        TextAlign::Left | TextAlign::Justify => start,
+    };
+    text_layout::draw_graphemes(row, x, end, &graphemes);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ui::cell::blank_canvas;
+    use crate::Size;
+
+    // draws the border into a canvas of the given size and returns its rows
+    fn draw(border: &Border, width: usize, height: usize) -> Vec<String> {
+        let mut canvas = blank_canvas(Size {
+            x: width,
+            y: height,
+        });
+        border.draw(
+            &mut canvas,
+            border.kind,
+            Color::Reset,
+            Attributes::default(),
+        );
+        canvas
+            .iter()
+            .map(|row| row.iter().map(|c| c.symbol.as_str()).collect())
+            .collect()
+    }
+
+    #[test]
+    fn draws_title_and_footer_between_the_corners() {
+        let border = Border::rounded()
+            .with_title("title", TextAlign::Left)
+            .with_footer("ok", TextAlign::Right);
+        assert_eq!(draw(&border, 9, 3), ["╭title──╮", "│       │", "╰─────ok╯"]);
+
+        let border = Border::plain().with_title("abc", TextAlign::Center);
+        assert_eq!(draw(&border, 7, 2)[0], "┌─abc─┐");
+    }
+
+    #[test]
+    fn labels_are_clipped_to_the_border() {
+        let border = Border::ascii().with_title("a long title", TextAlign::Center);
+        assert_eq!(draw(&border, 6, 2)[0], "+a lo+");
+    }
+
+    #[test]
+    fn sides_can_be_left_out() {
+        let border = Border::double()
+            .with_sides(Sides::horizontal())
+            .with_title("t", TextAlign::Left);
+        assert_eq!(draw(&border, 4, 3), ["t═══", "    ", "════"]);
+        assert_eq!(
+            border.width(),
+            Spacing {
+                x_start: 0,
+                x_end: 0,
+                y_start: 1,
+                y_end: 1,
+            }
+        );
+        assert_eq!(Border::none().width(), Spacing::default());
+    }
+}
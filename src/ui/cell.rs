@@ -0,0 +1,97 @@
+use crate::ui::style::{Attributes, Color, StyleSheet};
+use crate::ui::theme::Role;
+use crate::{Position, Rect, Size};
+use unicode_width::UnicodeWidthStr;
+
+/// content of a single terminal cell
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Cell {
+    /// grapheme cluster shown in the cell, empty for the continuation of a wide character
+    pub symbol: String,
+    pub fg: Color,
+    pub bg: Color,
+    pub attributes: Attributes,
+}
+impl Cell {
+    pub fn blank() -> Self {
+        Self::from(' ')
+    }
+
+    /// second cell occupied by a wide character, which is covered by the cell left of it
+    pub fn continuation() -> Self {
+        Self::from("")
+    }
+
+    pub fn is_continuation(&self) -> bool {
+        self.symbol.is_empty()
+    }
+
+    /// number of terminal columns the symbol occupies
+    pub fn width(&self) -> usize {
+        self.symbol.width()
+    }
+
+    pub fn with_fg(mut self, color: Color) -> Self {
+        self.fg = color;
+        self
+    }
+
+    pub fn with_bg(mut self, color: Color) -> Self {
+        self.bg = color;
+        self
+    }
+
+    pub fn with_attributes(mut self, attributes: Attributes) -> Self {
+        self.attributes = attributes;
+        self
+    }
+}
+impl From<char> for Cell {
+    fn from(char: char) -> Self {
+        Self::from(char.to_string().as_str())
+    }
+}
+impl From<&str> for Cell {
+    fn from(symbol: &str) -> Self {
+        Self {
+            symbol: symbol.to_string(),
+            fg: Color::Role(Role::Foreground),
+            bg: Color::Role(Role::Background),
+            attributes: Attributes::default(),
+        }
+    }
+}
+
+/// rows of cells drawn by a widget
+pub type Canvas = Vec<Vec<Cell>>;
+
+/// canvas filled with blank cells
+pub fn blank_canvas(size: Size) -> Canvas {
+    vec![vec![Cell::blank(); size.x]; size.y]
+}
+
+/// canvas filled with blank cells in the colors and attributes of `style`
+pub fn styled_canvas(size: Size, style: &StyleSheet) -> Canvas {
+    let mut canvas = vec![vec![Cell::blank().with_attributes(style.attributes); size.x]; size.y];
+    paint(
+        &mut canvas,
+        Rect::new(Position::default(), size),
+        style.foreground,
+        style.background,
+    );
+    canvas
+}
+
+/// sets the colors of every cell inside of `area`, colors that are `None` are kept
+pub fn paint(canvas: &mut Canvas, area: Rect, fg: Option<Color>, bg: Option<Color>) {
+    for row in canvas.iter_mut().skip(area.y).take(area.height) {
+        for cell in row.iter_mut().skip(area.x).take(area.width) {
+            if let Some(fg) = fg {
+                cell.fg = fg;
+            }
+            if let Some(bg) = bg {
+                cell.bg = bg;
+            }
+        }
+    }
+}
@@ -0,0 +1,182 @@
+use std::fs;
+use std::path::PathBuf;
+
+/// default number of entries that are kept
+pub const COMMAND_HISTORY_LIMIT: usize = 1000;
+
+/// submitted values of an input that can be browsed and searched like the history of a shell
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CommandHistory {
+    /// oldest first
+    pub entries: Vec<String>,
+    pub limit: usize,
+    /// file the entries are loaded from and saved to, one per line
+    pub path: Option<PathBuf>,
+    /// last error while reading or writing the file
+    pub error: Option<String>,
+
+    // browsed entry and the text that was typed before browsing
+    position: Option<usize>,
+    draft: String,
+}
+impl CommandHistory {
+    pub fn new() -> Self {
+        Self {
+            entries: Vec::new(),
+            limit: COMMAND_HISTORY_LIMIT,
+            path: None,
+            error: None,
+
+            position: None,
+            draft: String::new(),
+        }
+    }
+
+    /// loads the entries of the file if it exists and saves every new entry into it
+    pub fn with_file<P: Into<PathBuf>>(mut self, path: P) -> Self {
+        let path = path.into();
+        match fs::read_to_string(&path) {
+            Ok(content) => self.entries = content.lines().map(unescape).collect(),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => (),
+            Err(e) => self.error = Some(format!("{}: {}", path.display(), e)),
+        }
+        self.path = Some(path);
+        self.trim();
+        self
+    }
+
+    pub fn with_limit(mut self, limit: usize) -> Self {
+        self.limit = limit;
+        self.trim();
+        self
+    }
+
+    /// adds an entry, empty ones and repetitions of the last one are skipped
+    pub fn push(&mut self, entry: &str) {
+        self.reset();
+        if entry.is_empty() || self.entries.last().is_some_and(|last| last == entry) {
+            return;
+        }
+        self.entries.push(String::from(entry));
+        self.trim();
+        self.save();
+    }
+
+    /// writes all entries into the file, line breaks and backslashes are escaped
+    pub fn save(&mut self) {
+        if let Some(path) = &self.path {
+            let content: String = self
+                .entries
+                .iter()
+                .map(|entry| escape(entry) + "\n")
+                .collect();
+            self.error = fs::write(path, content)
+                .err()
+                .map(|e| format!("{}: {}", path.display(), e));
+        }
+    }
+
+    /// entry before the browsed one, `current` is restored when browsing past the newest entry
+    pub fn older(&mut self, current: &str) -> Option<String> {
+        let position = match self.position {
+            None => {
+                self.draft = String::from(current);
+                self.entries.len()
+            }
+            Some(position) => position,
+        };
+        let position = position.checked_sub(1)?;
+        self.position = Some(position);
+        Some(self.entries[position].clone())
+    }
+
+    pub fn newer(&mut self) -> Option<String> {
+        let position = self.position? + 1;
+        if position < self.entries.len() {
+            self.position = Some(position);
+            Some(self.entries[position].clone())
+        } else {
+            self.position = None;
+            Some(std::mem::take(&mut self.draft))
+        }
+    }
+
+    /// stops browsing, the next `older` starts at the newest entry again
+    pub fn reset(&mut self) {
+        self.position = None;
+        self.draft.clear();
+    }
+
+    /// index of the newest entry before `before` that contains `query`
+    pub fn search(&self, query: &str, before: usize) -> Option<usize> {
+        self.entries[..before.min(self.entries.len())]
+            .iter()
+            .rposition(|entry| entry.contains(query))
+    }
+
+    fn trim(&mut self) {
+        let excess = self.entries.len().saturating_sub(self.limit);
+        self.entries.drain(..excess);
+    }
+}
+impl Default for CommandHistory {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn escape(entry: &str) -> String {
+    entry.replace('\\', "\\\\").replace('\n', "\\n")
+}
+
+fn unescape(line: &str) -> String {
+    let mut entry = String::with_capacity(line.len());
+    let mut chars = line.chars();
+    while let Some(c) = chars.next() {
+        match (c, chars.clone().next()) {
+            ('\\', Some('n')) => {
+                entry.push('\n');
+                chars.next();
+            }
+            ('\\', Some('\\')) => {
+                entry.push('\\');
+                chars.next();
+            }
+            (c, _) => entry.push(c),
+        }
+    }
+    entry
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn escaped_entries_fit_on_one_line() {
+        for entry in ["plain", "two\nlines", "back\\slash", "\\n", "end\\"] {
+            let line = escape(entry);
+            assert!(!line.contains('\n'));
+            assert_eq!(unescape(&line), entry);
+        }
+        assert_eq!(escape("a\\n\nb"), "a\\\\n\\nb");
+    }
+
+    #[test]
+    fn browses_entries_and_restores_the_draft() {
+        let mut history = CommandHistory::new().with_limit(2);
+        for entry in ["one", "two", "two", "", "three"] {
+            history.push(entry);
+        }
+        assert_eq!(history.entries, ["two", "three"]);
+        assert_eq!(history.older("draft").as_deref(), Some("three"));
+        assert_eq!(history.older("three").as_deref(), Some("two"));
+        assert_eq!(history.older("two"), None);
+        assert_eq!(history.newer().as_deref(), Some("three"));
+        assert_eq!(history.newer().as_deref(), Some("draft"));
+        assert_eq!(history.newer(), None);
+        assert_eq!(history.search("t", 2), Some(1));
+        assert_eq!(history.search("w", 1), Some(0));
+        assert_eq!(history.search("w", 0), None);
+    }
+}
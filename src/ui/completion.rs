@@ -0,0 +1,154 @@
+use std::fmt;
+use std::sync::Arc;
+
+/// default number of suggestions shown at once
+pub const MAX_SHOWN: usize = 8;
+
+/// called with the text and the grapheme index of the cursor
+pub type CompleteFn = dyn Fn(&str, usize) -> Vec<String> + Send + Sync;
+
+/// source of the suggestions, every suggestion replaces the text in front of the cursor
+#[derive(Clone)]
+pub enum Completer {
+    List(Vec<String>),
+    Callback(Arc<CompleteFn>),
+}
+impl fmt::Debug for Completer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Completer::List(list) => f.debug_tuple("List").field(list).finish(),
+            Completer::Callback(_) => write!(f, "Callback(..)"),
+        }
+    }
+}
+// closures are only equal to themselves
+impl PartialEq for Completer {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Completer::List(list), Completer::List(other)) => list == other,
+            (Completer::Callback(callback), Completer::Callback(other)) => {
+                Arc::ptr_eq(callback, other)
+            }
+            _ => false,
+        }
+    }
+}
+impl Eq for Completer {}
+
+/// how suggestions are matched against the text in front of the cursor
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum Matching {
+    /// suggestions starting with the text, in the order of the completer
+    #[default]
+    Prefix,
+    /// suggestions containing the characters of the text in order, ignoring case, best matches first
+    Fuzzy,
+}
+
+/// suggestions of an input shown in a dropdown below it
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Completion {
+    pub completer: Completer,
+    pub matching: Matching,
+    pub max_shown: usize,
+}
+impl Completion {
+    pub fn list<S: Into<String>>(suggestions: Vec<S>) -> Self {
+        Self::new(Completer::List(
+            suggestions.into_iter().map(Into::into).collect(),
+        ))
+    }
+
+    pub fn callback<F: Fn(&str, usize) -> Vec<String> + Send + Sync + 'static>(
+        callback: F,
+    ) -> Self {
+        Self::new(Completer::Callback(Arc::new(callback)))
+    }
+
+    fn new(completer: Completer) -> Self {
+        Self {
+            completer,
+            matching: Matching::Prefix,
+            max_shown: MAX_SHOWN,
+        }
+    }
+
+    pub fn with_fuzzy(mut self) -> Self {
+        self.matching = Matching::Fuzzy;
+        self
+    }
+
+    pub fn with_max_shown(mut self, max_shown: usize) -> Self {
+        self.max_shown = max_shown;
+        self
+    }
+
+    /// matching suggestions for the text in front of `cursor`, which itself is no suggestion
+    pub fn suggestions(&self, text: &str, cursor: usize, before_cursor: &str) -> Vec<String> {
+        let candidates = match &self.completer {
+            Completer::List(list) => list.clone(),
+            Completer::Callback(callback) => callback(text, cursor),
+        };
+        let candidates = candidates
+            .into_iter()
+            .filter(|candidate| candidate != before_cursor);
+
+        match self.matching {
+            Matching::Prefix => candidates
+                .filter(|candidate| candidate.starts_with(before_cursor))
+                .collect(),
+            Matching::Fuzzy => {
+                let mut scored: Vec<(i32, String)> = candidates
+                    .filter_map(|candidate| {
+                        Some((fuzzy_score(before_cursor, &candidate)?, candidate))
+                    })
+                    .collect();
+                // the sort is stable, so equal scores keep the order of the completer
+                scored.sort_by_key(|(score, _)| -score);
+                scored.into_iter().map(|(_, candidate)| candidate).collect()
+            }
+        }
+    }
+}
+
+/// score of the characters of `pattern` appearing in order in `candidate`, ignoring case.
+/// consecutive characters and characters at the start of words score higher
+pub fn fuzzy_score(pattern: &str, candidate: &str) -> Option<i32> {
+    let candidate: Vec<char> = candidate.chars().collect();
+    let mut score: i32 = 0;
+    let mut next: usize = 0;
+    let mut last: Option<usize> = None;
+    for p in pattern.chars().flat_map(char::to_lowercase) {
+        let found = (next..candidate.len())
+            .find(|i| candidate[*i].to_lowercase().eq(std::iter::once(p)))?;
+
+        score += 1;
+        if last.is_some_and(|last| last + 1 == found) {
+            score += 5;
+        }
+        if found == 0 || !candidate[found - 1].is_alphanumeric() {
+            score += 3;
+        }
+        // gaps make the match weaker
+        score -= (found - next).min(3) as i32;
+
+        last = Some(found);
+        next = found + 1;
+    }
+    Some(score)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fuzzy_score_prefers_consecutive_and_word_starts() {
+        assert_eq!(fuzzy_score("xyz", "abc"), None);
+        assert_eq!(fuzzy_score("ba", "abc"), None);
+        assert_eq!(fuzzy_score("", "abc"), Some(0));
+        assert!(fuzzy_score("ABC", "abc").is_some());
+        assert!(fuzzy_score("ab", "abc") > fuzzy_score("ab", "axb"));
+        assert!(fuzzy_score("fb", "foo_bar") > fuzzy_score("fb", "fooxbar"));
+    }
+}
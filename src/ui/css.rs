@@ -0,0 +1,788 @@
+use super::border::BorderType;
+use super::style::{
+    Anchor, Attributes, Color, Length, Overflow, Spacing, StateStyle, Style, StyleSheet, TextAlign,
+    VerticalAlign, Wrap,
+};
+use super::theme::{Role, Theme};
+use super::widget::container::Container;
+use super::widget::{Widget, Widgets};
+use std::fmt;
+
+/// error of `Css::parse`, `line` is counted from 1
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseError {
+    pub line: usize,
+    pub message: String,
+}
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+impl std::error::Error for ParseError {}
+
+/// stylesheet with CSS-like rules that set the `StyleSheet` of every widget matched by their selectors
+///
+/// ```text
+/// /* selectors by type, #id, .class and :state */
+/// Button { text-align: center; vertical-align: middle; }
+/// #submit, Button.danger:focused { color: error; text-style: bold; }
+/// ```
+///
+/// rules with a higher specificity win, rules with the same specificity are applied in order.
+/// state selectors (`:focused`, `:hovered`, `:pressed`, `:invalid`, `:disabled`) set the overrides of that state
+/// and only support `color`, `background`, `border-color`, `border` and `text-style`.
+/// a `@theme { accent: magenta; }` block sets colors of the roles of the theme
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Css {
+    rules: Vec<Rule>,
+    theme: Vec<(Role, Color)>,
+}
+impl Css {
+    pub fn parse(source: &str) -> Result<Self, ParseError> {
+        let source = strip_comments(source);
+        let line_at = |offset: usize| source[..offset].matches('\n').count() + 1;
+
+        let mut rules: Vec<Rule> = Vec::new();
+        let mut theme: Vec<(Role, Color)> = Vec::new();
+        let mut position: usize = 0;
+        while let Some(open) = source[position..].find('{').map(|i| i + position) {
+            let close = match source[open..].find('}') {
+                Some(i) => i + open,
+                None => return Err(error(line_at(open), "missing `}`")),
+            };
+
+            if source[position..open].trim() == "@theme" {
+                let mut start = open + 1;
+                for declaration in split_declarations(&source[open + 1..close]) {
+                    let line = line_at(start + declaration.len() - declaration.trim_start().len());
+                    start += declaration.len() + 1;
+                    if declaration.trim().is_empty() {
+                        continue;
+                    }
+                    theme.push(
+                        parse_theme_color(declaration.trim())
+                            .map_err(|message| ParseError { line, message })?,
+                    );
+                }
+                position = close + 1;
+                continue;
+            }
+
+            let mut selectors: Vec<Selector> = Vec::new();
+            for selector in source[position..open].split(',') {
+                selectors.push(
+                    Selector::parse(selector.trim()).map_err(|message| ParseError {
+                        line: line_at(open),
+                        message,
+                    })?,
+                );
+            }
+
+            let mut declarations: Vec<Declaration> = Vec::new();
+            let mut start = open + 1;
+            for declaration in split_declarations(&source[open + 1..close]) {
+                let line = line_at(start + declaration.len() - declaration.trim_start().len());
+                start += declaration.len() + 1;
+                if declaration.trim().is_empty() {
+                    continue;
+                }
+
+                let declaration = Declaration::parse(declaration.trim())
+                    .map_err(|message| ParseError { line, message })?;
+                if !declaration.is_stateful() && selectors.iter().any(|s| s.state.is_some()) {
+                    return Err(error(
+                        line,
+                        "only colors, border and text-style depend on the state",
+                    ));
+                }
+                declarations.push(declaration);
+            }
+
+            for selector in selectors {
+                rules.push(Rule {
+                    selector,
+                    declarations: declarations.clone(),
+                });
+            }
+            position = close + 1;
+        }
+
+        if !source[position..].trim().is_empty() {
+            return Err(error(line_at(source.len()), "expected `{`"));
+        }
+        Ok(Self { rules, theme })
+    }
+
+    /// sets the colors of the `@theme` blocks
+    pub fn apply_theme(&self, theme: &mut Theme) {
+        for (role, color) in self.theme.iter() {
+            theme.set_color(*role, *color);
+        }
+    }
+
+    /// styles the container and all of its content, call `apply_style` afterwards to lay it out again.
+    /// the rules cascade from the style every widget had before the first stylesheet was applied,
+    /// so applying another stylesheet replaces the previous one
+    pub fn apply(&self, container: &mut Container) {
+        let base = container
+            .get_base_style()
+            .unwrap_or_else(|| container.get_style());
+        let style = self.style_for(
+            "Container",
+            container.get_id(),
+            &container.get_classes(),
+            base,
+        );
+        container.set_style(style);
+        container.set_base_style(Some(base));
+
+        for widget in container.content.iter_mut() {
+            match widget {
+                Widgets::Container(container) => self.apply(container),
+                widget => {
+                    let base = widget
+                        .get_base_style()
+                        .unwrap_or_else(|| widget.get_style());
+                    let style = self.style_for(
+                        widget.type_name(),
+                        widget.get_id(),
+                        &widget.get_classes(),
+                        base,
+                    );
+                    widget.set_style(style);
+                    widget.set_base_style(Some(base));
+                }
+            }
+        }
+    }
+
+    /// `style` with the declarations of every matching rule applied
+    pub fn style_for(
+        &self,
+        type_name: &str,
+        id: Option<String>,
+        classes: &[String],
+        mut style: StyleSheet,
+    ) -> StyleSheet {
+        let mut matching: Vec<&Rule> = self
+            .rules
+            .iter()
+            .filter(|rule| rule.selector.matches(type_name, id.as_deref(), classes))
+            .collect();
+        // the sort is stable, so rules of the same specificity stay in order
+        matching.sort_by_key(|rule| rule.selector.specificity());
+
+        for rule in matching {
+            for declaration in rule.declarations.iter() {
+                match rule.selector.state {
+                    None => declaration.apply(&mut style),
+                    Some(state) => declaration.apply_state(match state {
+                        StateName::Focused => &mut style.focused,
+                        StateName::Hovered => &mut style.hovered,
+                        StateName::Pressed => &mut style.pressed,
+                        StateName::Invalid => &mut style.invalid,
+                        StateName::Disabled => &mut style.disabled,
+                    }),
+                }
+            }
+        }
+        style
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+struct Rule {
+    selector: Selector,
+    declarations: Vec<Declaration>,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum StateName {
+    Focused,
+    Hovered,
+    Pressed,
+    Invalid,
+    Disabled,
+}
+
+// compound selector like `Button#submit.danger:focused`, `*` matches every type
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+struct Selector {
+    type_name: Option<String>,
+    id: Option<String>,
+    classes: Vec<String>,
+    state: Option<StateName>,
+}
+impl Selector {
+    fn parse(text: &str) -> Result<Self, String> {
+        if text.is_empty() {
+            return Err(String::from("empty selector"));
+        }
+
+        let mut selector = Selector::default();
+        // every part starts at a prefix, the type has none
+        let mut parts: Vec<(char, String)> = Vec::new();
+        for char in text.chars() {
+            match char {
+                '#' | '.' | ':' => parts.push((char, String::new())),
+                c if c.is_alphanumeric() || c == '-' || c == '_' || c == '*' => {
+                    match parts.last_mut() {
+                        Some((_, name)) => name.push(c),
+                        None => parts.push((' ', c.to_string())),
+                    }
+                }
+                c => return Err(format!("unexpected `{}` in selector `{}`", c, text)),
+            }
+        }
+
+        for (prefix, name) in parts {
+            if name.is_empty() {
+                return Err(format!(
+                    "missing name after `{}` in selector `{}`",
+                    prefix, text
+                ));
+            }
+            match prefix {
+                ' ' if name == "*" => (),
+                ' ' => selector.type_name = Some(name),
+                '#' if selector.id.is_none() => selector.id = Some(name),
+                '#' => return Err(format!("more than one id in selector `{}`", text)),
+                '.' => selector.classes.push(name),
+                _ if selector.state.is_some() => {
+                    return Err(format!("more than one state in selector `{}`", text))
+                }
+                _ => {
+                    selector.state = Some(match name.as_str() {
+                        "focused" | "focus" => StateName::Focused,
+                        "hovered" | "hover" => StateName::Hovered,
+                        "pressed" | "active" => StateName::Pressed,
+                        "invalid" => StateName::Invalid,
+                        "disabled" => StateName::Disabled,
+                        _ => return Err(format!("unknown state `:{}`", name)),
+                    })
+                }
+            }
+        }
+        Ok(selector)
+    }
+
+    fn matches(&self, type_name: &str, id: Option<&str>, classes: &[String]) -> bool {
+        self.type_name.as_ref().is_none_or(|t| t == type_name)
+            && self.id.as_ref().is_none_or(|i| Some(i.as_str()) == id)
+            && self.classes.iter().all(|c| classes.contains(c))
+    }
+
+    // ids, then classes and states, then types
+    fn specificity(&self) -> (usize, usize, usize) {
+        (
+            self.id.iter().count(),
+            self.classes.len() + self.state.iter().count(),
+            self.type_name.iter().count(),
+        )
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Declaration {
+    Width(Length),
+    Height(Length),
+    Margin(Spacing),
+    Padding(Spacing),
+    PlacementX(Anchor),
+    PlacementY(Anchor),
+    ZIndex(i32),
+    TextAlign(TextAlign),
+    VerticalAlign(VerticalAlign),
+    Wrap(Wrap),
+    Overflow(Overflow),
+    TabWidth(usize),
+    Foreground(Option<Color>),
+    Background(Option<Color>),
+    BorderColor(Option<Color>),
+    Border(BorderType),
+    Attributes(Attributes),
+}
+impl Declaration {
+    fn parse(text: &str) -> Result<Self, String> {
+        let (name, value) = match text.split_once(':') {
+            Some((name, value)) => (name.trim(), value.trim()),
+            None => return Err(format!("expected `:` in `{}`", text)),
+        };
+        let invalid = || format!("invalid value `{}` for `{}`", value, name);
+
+        Ok(match name {
+            "width" => Declaration::Width(parse_length(value).ok_or_else(invalid)?),
+            "height" => Declaration::Height(parse_length(value).ok_or_else(invalid)?),
+            "margin" => Declaration::Margin(parse_spacing(value).ok_or_else(invalid)?),
+            "padding" => Declaration::Padding(parse_spacing(value).ok_or_else(invalid)?),
+            "placement-x" => Declaration::PlacementX(parse_anchor(value).ok_or_else(invalid)?),
+            "placement-y" => Declaration::PlacementY(parse_anchor(value).ok_or_else(invalid)?),
+            "z-index" => Declaration::ZIndex(value.parse().map_err(|_| invalid())?),
+            "text-align" => Declaration::TextAlign(match value {
+                "left" => TextAlign::Left,
+                "center" => TextAlign::Center,
+                "right" => TextAlign::Right,
+                "justify" => TextAlign::Justify,
+                _ => return Err(invalid()),
+            }),
+            "vertical-align" => Declaration::VerticalAlign(match value {
+                "top" => VerticalAlign::Top,
+                "middle" => VerticalAlign::Middle,
+                "bottom" => VerticalAlign::Bottom,
+                _ => return Err(invalid()),
+            }),
+            "wrap" => Declaration::Wrap(match value {
+                "none" => Wrap::None,
+                "character" => Wrap::Character,
+                "word" => Wrap::Word,
+                _ => return Err(invalid()),
+            }),
+            "overflow" => Declaration::Overflow(match value {
+                "clip" => Overflow::Clip,
+                "ellipsis" => Overflow::Ellipsis,
+                "ellipsis-middle" => Overflow::EllipsisMiddle,
+                _ => return Err(invalid()),
+            }),
+            "tab-width" => Declaration::TabWidth(value.parse().map_err(|_| invalid())?),
+            "color" => Declaration::Foreground(parse_color(value).ok_or_else(invalid)?),
+            "background" => Declaration::Background(parse_color(value).ok_or_else(invalid)?),
+            "border-color" => Declaration::BorderColor(parse_color(value).ok_or_else(invalid)?),
+            "border" => Declaration::Border(parse_border(value).ok_or_else(invalid)?),
+            "text-style" => Declaration::Attributes(parse_attributes(value).ok_or_else(invalid)?),
+            _ => return Err(format!("unknown property `{}`", name)),
+        })
+    }
+
+    fn is_stateful(&self) -> bool {
+        matches!(
+            self,
+            Declaration::Foreground(_)
+                | Declaration::Background(_)
+                | Declaration::BorderColor(_)
+                | Declaration::Border(_)
+                | Declaration::Attributes(_)
+        )
+    }
+
+    fn apply(&self, style: &mut StyleSheet) {
+        match self.clone() {
+            Declaration::Width(width) => style.width = width,
+            Declaration::Height(height) => style.height = height,
+            Declaration::Margin(margin) => style.margin = margin,
+            Declaration::Padding(padding) => style.padding = padding,
+            Declaration::PlacementX(x) => style.placement.x = x,
+            Declaration::PlacementY(y) => style.placement.y = y,
+            Declaration::ZIndex(z_index) => style.z_index = z_index,
+            Declaration::TextAlign(align) => style.text_align = align,
+            Declaration::VerticalAlign(align) => style.vertical_align = align,
+            Declaration::Wrap(wrap) => style.wrap = wrap,
+            Declaration::Overflow(overflow) => style.overflow = overflow,
+            Declaration::TabWidth(tab_width) => style.tab_width = tab_width,
+            Declaration::Foreground(color) => style.foreground = color,
+            Declaration::Background(color) => style.background = color,
+            Declaration::BorderColor(color) => style.border_color = color,
+            Declaration::Border(border) => style.border = Some(border),
+            Declaration::Attributes(attributes) => style.attributes = attributes,
+        }
+    }
+
+    fn apply_state(&self, state: &mut StateStyle) {
+        match self.clone() {
+            Declaration::Foreground(color) => state.foreground = color,
+            Declaration::Background(color) => state.background = color,
+            Declaration::BorderColor(color) => state.border_color = color,
+            Declaration::Border(border) => state.border = Some(border),
+            Declaration::Attributes(attributes) => state.attributes = Some(attributes),
+            _ => (),
+        }
+    }
+}
+
+// `role: color` inside of a `@theme` block
+fn parse_theme_color(text: &str) -> Result<(Role, Color), String> {
+    let (name, value) = match text.split_once(':') {
+        Some((name, value)) => (name.trim(), value.trim()),
+        None => return Err(format!("expected `:` in `{}`", text)),
+    };
+    let role = parse_role(name).ok_or_else(|| format!("unknown role `{}`", name))?;
+    match parse_color(value) {
+        Some(Some(color)) => Ok((role, color)),
+        _ => Err(format!("invalid color `{}` for `{}`", value, name)),
+    }
+}
+
+fn error(line: usize, message: &str) -> ParseError {
+    ParseError {
+        line,
+        message: String::from(message),
+    }
+}
+
+// comments are replaced by spaces, so line numbers stay the same
+fn strip_comments(source: &str) -> String {
+    let mut result = String::new();
+    let mut rest = source;
+    while let Some(start) = rest.find("/*") {
+        result.push_str(&rest[..start]);
+        let end = rest[start..]
+            .find("*/")
+            .map_or(rest.len(), |i| start + i + 2);
+        result.extend(
+            rest[start..end]
+                .chars()
+                .map(|c| if c == '\n' { '\n' } else { ' ' }),
+        );
+        rest = &rest[end..];
+    }
+    result.push_str(rest);
+    result
+}
+
+// splits at `;` outside of quotes, every part keeps its whitespace
+fn split_declarations(body: &str) -> Vec<&str> {
+    let mut parts: Vec<&str> = Vec::new();
+    let mut quoted = false;
+    let mut start = 0;
+    for (i, char) in body.char_indices() {
+        match char {
+            '"' => quoted = !quoted,
+            ';' if !quoted => {
+                parts.push(&body[start..i]);
+                start = i + 1;
+            }
+            _ => (),
+        }
+    }
+    parts.push(&body[start..]);
+    parts
+}
+
+fn parse_length(value: &str) -> Option<Length> {
+    if value == "auto" {
+        return Some(Length::Auto);
+    }
+    match value.strip_suffix('%') {
+        Some(percent) => percent
+            .parse::<f32>()
+            .ok()
+            .map(|p| Length::Relative(p / 100.0)),
+        None => value.parse::<f32>().ok().map(Length::Absolute),
+    }
+}
+
+// one to four sizes in the order top, right, bottom, left like in CSS
+fn parse_spacing(value: &str) -> Option<Spacing> {
+    let sizes: Vec<usize> = value
+        .split_whitespace()
+        .map(|size| size.parse().ok())
+        .collect::<Option<Vec<usize>>>()?;
+    let (top, right, bottom, left) = match sizes[..] {
+        [all] => (all, all, all, all),
+        [vertical, horizontal] => (vertical, horizontal, vertical, horizontal),
+        [top, horizontal, bottom] => (top, horizontal, bottom, horizontal),
+        [top, right, bottom, left] => (top, right, bottom, left),
+        _ => return None,
+    };
+    Some(Spacing {
+        x_start: left,
+        x_end: right,
+        y_start: top,
+        y_end: bottom,
+    })
+}
+
+fn parse_anchor(value: &str) -> Option<Anchor> {
+    let words: Vec<&str> = value.split_whitespace().collect();
+    match words[..] {
+        ["center"] => Some(Anchor::Center),
+        ["start"] => Some(Anchor::Start(0)),
+        ["end"] => Some(Anchor::End(0)),
+        ["start", offset] => offset.parse().ok().map(Anchor::Start),
+        ["end", offset] => offset.parse().ok().map(Anchor::End),
+        _ => None,
+    }
+}
+
+// `none` is parsed as `Some(None)`, which resets the color
+fn parse_color(value: &str) -> Option<Option<Color>> {
+    if let Some(hex) = value.strip_prefix('#') {
+        let channel = |i: usize| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok();
+        return match hex.len() {
+            6 => Some(Some(Color::Rgb {
+                r: channel(0)?,
+                g: channel(2)?,
+                b: channel(4)?,
+            })),
+            _ => None,
+        };
+    }
+    if let Some(index) = value
+        .strip_prefix("ansi(")
+        .and_then(|v| v.strip_suffix(')'))
+    {
+        return index.trim().parse().ok().map(|i| Some(Color::AnsiValue(i)));
+    }
+
+    Some(Some(match value {
+        "none" => return Some(None),
+        "reset" => Color::Reset,
+        "black" => Color::Black,
+        "dark-grey" => Color::DarkGrey,
+        "red" => Color::Red,
+        "dark-red" => Color::DarkRed,
+        "green" => Color::Green,
+        "dark-green" => Color::DarkGreen,
+        "yellow" => Color::Yellow,
+        "dark-yellow" => Color::DarkYellow,
+        "blue" => Color::Blue,
+        "dark-blue" => Color::DarkBlue,
+        "magenta" => Color::Magenta,
+        "dark-magenta" => Color::DarkMagenta,
+        "cyan" => Color::Cyan,
+        "dark-cyan" => Color::DarkCyan,
+        "white" => Color::White,
+        "grey" => Color::Grey,
+        // roles of the theme
+        role => Color::Role(parse_role(role)?),
+    }))
+}
+
+fn parse_role(name: &str) -> Option<Role> {
+    Some(match name {
+        "foreground" => Role::Foreground,
+        "background" => Role::Background,
+        "primary" => Role::Primary,
+        "accent" => Role::Accent,
+        "success" => Role::Success,
+        "warning" => Role::Warning,
+        "error" => Role::Error,
+        "border" => Role::Border,
+        "border-focused" => Role::BorderFocused,
+        "selection" => Role::Selection,
+        "placeholder" => Role::Placeholder,
+        "disabled" => Role::Disabled,
+        _ => return None,
+    })
+}
+
+// name of a preset or six symbols in quotes:
+// upper left, upper right, bottom right, bottom left, horizontal and vertical
+fn parse_border(value: &str) -> Option<BorderType> {
+    Some(match value {
+        "plain" => BorderType::Plain,
+        "rounded" => BorderType::Rounded,
+        "double" => BorderType::Double,
+        "thick" => BorderType::Thick,
+        "ascii" => BorderType::Ascii,
+        "none" => BorderType::None,
+        _ => {
+            let symbols: Vec<char> = value
+                .strip_prefix('"')?
+                .strip_suffix('"')?
+                .chars()
+                .collect();
+            BorderType::Custom(symbols.try_into().ok()?)
+        }
+    })
+}
+
+fn parse_attributes(value: &str) -> Option<Attributes> {
+    let mut attributes = Attributes::default();
+    for name in value.split_whitespace() {
+        match name {
+            "none" => (),
+            "bold" => attributes.bold = true,
+            "dim" => attributes.dim = true,
+            "italic" => attributes.italic = true,
+            "underlined" => attributes.underlined = true,
+            "reversed" => attributes.reversed = true,
+            "crossed-out" => attributes.crossed_out = true,
+            _ => return None,
+        }
+    }
+    Some(attributes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ui::widget::button::Button;
+    use crate::ui::widget::container::ContainerKind;
+
+    fn style_for(css: &Css, type_name: &str, id: Option<&str>, classes: &[&str]) -> StyleSheet {
+        let classes: Vec<String> = classes.iter().map(|c| c.to_string()).collect();
+        css.style_for(
+            type_name,
+            id.map(String::from),
+            &classes,
+            StyleSheet::default(),
+        )
+    }
+
+    #[test]
+    fn parses_selectors() {
+        let selector = Selector::parse("Button#submit.danger.big:focus").unwrap();
+        assert_eq!(selector.type_name.as_deref(), Some("Button"));
+        assert_eq!(selector.id.as_deref(), Some("submit"));
+        assert_eq!(selector.classes, ["danger", "big"]);
+        assert_eq!(selector.state, Some(StateName::Focused));
+        assert_eq!(selector.specificity(), (1, 3, 1));
+
+        assert_eq!(Selector::parse("*").unwrap(), Selector::default());
+        assert!(Selector::parse("#a#b").is_err());
+        assert!(Selector::parse("Button.").is_err());
+        assert!(Selector::parse("a > b").is_err());
+        assert!(Selector::parse(":hover:focus").is_err());
+    }
+
+    #[test]
+    fn matches_type_id_and_classes() {
+        let selector = Selector::parse("Button.danger").unwrap();
+        let classes = [String::from("danger"), String::from("big")];
+        assert!(selector.matches("Button", None, &classes));
+        assert!(!selector.matches("Text", None, &classes));
+        assert!(!selector.matches("Button", None, &classes[1..]));
+        assert!(Selector::parse("#ok")
+            .unwrap()
+            .matches("Text", Some("ok"), &[]));
+        assert!(!Selector::parse("#ok").unwrap().matches("Text", None, &[]));
+    }
+
+    #[test]
+    fn parses_values() {
+        assert_eq!(
+            parse_spacing("1 2 3"),
+            Some(Spacing {
+                x_start: 2,
+                x_end: 2,
+                y_start: 1,
+                y_end: 3,
+            })
+        );
+        assert_eq!(
+            parse_spacing("1 2"),
+            Some(Spacing {
+                x_start: 2,
+                x_end: 2,
+                y_start: 1,
+                y_end: 1,
+            })
+        );
+        assert_eq!(parse_spacing("1 2 3 4 5"), None);
+        assert_eq!(parse_spacing("a"), None);
+
+        assert_eq!(
+            parse_color("#ff8000"),
+            Some(Some(Color::Rgb {
+                r: 255,
+                g: 128,
+                b: 0
+            }))
+        );
+        assert_eq!(parse_color("ansi(42)"), Some(Some(Color::AnsiValue(42))));
+        assert_eq!(parse_color("dark-red"), Some(Some(Color::DarkRed)));
+        assert_eq!(parse_color("accent"), Some(Some(Color::Role(Role::Accent))));
+        assert_eq!(parse_color("none"), Some(None));
+        assert_eq!(parse_color("#fff"), None);
+        assert_eq!(parse_color("purple"), None);
+
+        assert_eq!(parse_length("50%"), Some(Length::Relative(0.5)));
+        assert_eq!(parse_length("12"), Some(Length::Absolute(12.0)));
+        assert_eq!(parse_length("auto"), Some(Length::Auto));
+        assert_eq!(parse_anchor("end 2"), Some(Anchor::End(2)));
+    }
+
+    #[test]
+    fn applies_rules_by_specificity_then_order() {
+        let css = Css::parse(
+            "#ok { color: green; }
+             Button.danger { color: red; }
+             Button { color: blue; tab-width: 2; }
+             Button { tab-width: 8; }",
+        )
+        .unwrap();
+
+        let button = style_for(&css, "Button", None, &[]);
+        assert_eq!(button.foreground, Some(Color::Blue));
+        assert_eq!(button.tab_width, 8);
+        let danger = style_for(&css, "Button", None, &["danger"]);
+        assert_eq!(danger.foreground, Some(Color::Red));
+        let ok = style_for(&css, "Button", Some("ok"), &["danger"]);
+        assert_eq!(ok.foreground, Some(Color::Green));
+        assert_eq!(style_for(&css, "Text", None, &[]), StyleSheet::default());
+    }
+
+    #[test]
+    fn state_selectors_set_the_overrides_of_the_state() {
+        let css = Css::parse("Button:focused { color: accent; text-style: bold; }").unwrap();
+        let style = style_for(&css, "Button", None, &[]);
+        assert_eq!(style.foreground, None);
+        assert_eq!(style.focused.foreground, Some(Color::Role(Role::Accent)));
+        assert!(style.focused.attributes.unwrap().bold);
+
+        let error = Css::parse("Button:focused {\n width: 10; }").unwrap_err();
+        assert_eq!(error.line, 2);
+    }
+
+    #[test]
+    fn reports_errors_with_their_line() {
+        let error =
+            Css::parse("/* a\ncomment */\nText {\n  color: red;\n  colour: red;\n}").unwrap_err();
+        assert_eq!(error.line, 5);
+        assert_eq!(error.message, "unknown property `colour`");
+
+        assert_eq!(Css::parse("Text {\n color: red;").unwrap_err().line, 1);
+        assert_eq!(Css::parse("Text {}\n\nButton").unwrap_err().line, 3);
+        assert_eq!(Css::parse("@theme {\n sky: red; }").unwrap_err().line, 2);
+    }
+
+    #[test]
+    fn comments_and_quoted_semicolons_are_ignored() {
+        let css = Css::parse("/* Text { color: red; } */ Text { border: \";;;;;;\"; }").unwrap();
+        let style = style_for(&css, "Text", None, &[]);
+        assert_eq!(style.foreground, None);
+        assert_eq!(style.border, Some(BorderType::Custom([';'; 6])));
+    }
+
+    #[test]
+    fn theme_block_sets_role_colors() {
+        let css = Css::parse("@theme { accent: magenta; error: #ff0000; }").unwrap();
+        let mut theme = Theme::default();
+        css.apply_theme(&mut theme);
+        assert_eq!(theme.color(Role::Accent), Color::Magenta);
+        assert_eq!(theme.color(Role::Error), Color::Rgb { r: 255, g: 0, b: 0 });
+    }
+    #[test]
+    fn applying_another_stylesheet_replaces_the_previous_one() {
+        let base = StyleSheet {
+            tab_width: 3,
+            ..StyleSheet::default()
+        };
+        let mut container = Container::new(
+            ContainerKind::Column,
+            vec![Widgets::Button(
+                Button::new("ok", base).with_class("danger"),
+            )],
+            base,
+        );
+
+        Css::parse(".danger { color: red; } Container { tab-width: 6; }")
+            .unwrap()
+            .apply(&mut container);
+        assert_eq!(
+            container.content[0].get_style().foreground,
+            Some(Color::Red)
+        );
+        assert_eq!(container.get_style().tab_width, 6);
+
+        Css::parse("Button { wrap: none; }")
+            .unwrap()
+            .apply(&mut container);
+        let button = container.content[0].get_style();
+        assert_eq!(button.foreground, None);
+        assert_eq!(button.wrap, Wrap::None);
+        assert_eq!(button.tab_width, 3);
+        assert_eq!(container.get_style().tab_width, 3);
+    }
+}
@@ -0,0 +1,143 @@
+/// default number of steps that can be undone
+pub const HISTORY_LIMIT: usize = 100;
+
+/// kind of an edit, consecutive edits of the same kind are undone in one step
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Edit {
+    /// typing
+    Insert,
+    /// backspace and delete
+    Remove,
+    /// pasting, cutting, line breaks, ... are always a step of their own
+    Other,
+}
+
+/// undo and redo stacks of snapshots of an editable widget
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct History<T> {
+    undo: Vec<T>,
+    redo: Vec<T>,
+    // kind of the last edit, as long as it can be continued
+    group: Option<Edit>,
+    // edits of every kind continue the group, see `begin_change`
+    change: bool,
+    pub limit: usize,
+}
+impl<T> History<T> {
+    pub fn new() -> Self {
+        Self {
+            undo: Vec::new(),
+            redo: Vec::new(),
+            group: None,
+            change: false,
+            limit: HISTORY_LIMIT,
+        }
+    }
+
+    /// records the snapshot from before an edit, unless the edit continues the last one
+    pub fn record(&mut self, before: T, edit: Edit) {
+        self.redo.clear();
+        if self.change && self.group.is_some() {
+            return;
+        }
+        if edit != Edit::Other && self.group == Some(edit) {
+            return;
+        }
+        self.group = Some(edit);
+
+        self.undo.push(before);
+        if self.undo.len() > self.limit {
+            self.undo.remove(0);
+        }
+    }
+
+    /// the next edit starts a new step, for example after the cursor was moved
+    pub fn break_group(&mut self) {
+        self.group = None;
+    }
+
+    /// edits of every kind are undone in one step until `end_change`,
+    /// like a change of vi from its command until the insert mode is left
+    pub fn begin_change(&mut self) {
+        self.group = None;
+        self.change = true;
+    }
+
+    pub fn end_change(&mut self) {
+        self.group = None;
+        self.change = false;
+    }
+
+    /// snapshot to go back to, `current` can be restored with `redo`
+    pub fn undo(&mut self, current: T) -> Option<T> {
+        let snapshot = self.undo.pop()?;
+        self.redo.push(current);
+        self.end_change();
+        Some(snapshot)
+    }
+
+    pub fn redo(&mut self, current: T) -> Option<T> {
+        let snapshot = self.redo.pop()?;
+        self.undo.push(current);
+        self.end_change();
+        Some(snapshot)
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.undo.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.redo.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.undo.clear();
+        self.redo.clear();
+        self.end_change();
+    }
+}
+impl<T> Default for History<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn edits_of_the_same_kind_are_one_step() {
+        let mut history = History::new();
+        history.record("", Edit::Insert);
+        history.record("a", Edit::Insert);
+        history.record("ab", Edit::Remove);
+        history.record("a", Edit::Other);
+        history.record("a\n", Edit::Other);
+
+        assert_eq!(history.undo("a\nb"), Some("a\n"));
+        assert_eq!(history.undo("a\n"), Some("a"));
+        assert_eq!(history.undo("a"), Some("ab"));
+        assert_eq!(history.undo("ab"), Some(""));
+        assert_eq!(history.undo(""), None);
+
+        assert_eq!(history.redo(""), Some("ab"));
+        assert!(history.can_undo() && history.can_redo());
+        // a new edit drops what could be redone
+        history.record("ab", Edit::Insert);
+        assert!(!history.can_redo());
+    }
+
+    #[test]
+    fn break_group_and_limit() {
+        let mut history = History::new();
+        history.limit = 2;
+        history.record(0, Edit::Insert);
+        history.break_group();
+        history.record(1, Edit::Insert);
+        history.record(2, Edit::Remove);
+        assert_eq!(history.undo(3), Some(2));
+        assert_eq!(history.undo(2), Some(1));
+        assert_eq!(history.undo(1), None);
+    }
+}
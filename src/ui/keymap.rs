@@ -0,0 +1,525 @@
+use crate::events::{KeyCode, KeyMod};
+use crate::ui::cell::{paint, Canvas};
+use crate::ui::style::Color;
+use crate::ui::text_layout;
+use crate::ui::theme::Role;
+use crate::Rect;
+use std::ops::Range;
+use std::sync::atomic::{AtomicU8, Ordering};
+use unicode_segmentation::UnicodeSegmentation;
+
+static GLOBAL: AtomicU8 = AtomicU8::new(0);
+
+// columns of the `[N]` indicator of the vi keymap and the space in front of it
+pub(crate) const MODE_INDICATOR_WIDTH: usize = 4;
+
+/// key bindings of inputs and text areas, layered over their default keys
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum Keymap {
+    #[default]
+    Default,
+    /// readline-like bindings, `Ctrl+A`, `Ctrl+E`, `Ctrl+K`, `Ctrl+U`, `Ctrl+W`, `Alt+B`, `Alt+F`, ...
+    Emacs,
+    /// normal mode with motions and operators, insert mode with the default keys
+    Vi,
+}
+
+/// keymap of every input and text area that does not set its own
+pub fn set_global(keymap: Keymap) {
+    GLOBAL.store(keymap as u8, Ordering::Relaxed);
+}
+
+pub fn global() -> Keymap {
+    match GLOBAL.load(Ordering::Relaxed) {
+        1 => Keymap::Emacs,
+        2 => Keymap::Vi,
+        _ => Keymap::Default,
+    }
+}
+
+/// target of the cursor relative to where it is
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Motion {
+    Left,
+    Right,
+    LineStart,
+    FirstNonBlank,
+    LineEnd,
+    /// start of the next word, like `w` of vi
+    NextWordStart,
+    /// start of the word in front of the cursor
+    PreviousWordStart,
+    /// behind the end of the word under or after the cursor
+    NextWordEnd,
+    /// on the last grapheme of the next word, like `e` of vi. operators include that grapheme
+    WordEnd,
+    /// the whole line with its line break, for `dd` and `yy`
+    Line,
+}
+
+/// what a key does in a keymap
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Action {
+    /// handled like this key without a keymap
+    Key(KeyMod, KeyCode),
+    Move(Motion),
+    /// removes the text between the cursor and the target into the clipboard
+    Cut(Motion),
+    Copy(Motion),
+    /// pastes the clipboard in front of or behind the cursor, with `lines` text ending with a
+    /// line break goes above or below the line of the cursor like in vi
+    Paste {
+        after: bool,
+        lines: bool,
+    },
+    Undo,
+    Redo,
+    /// accepts the suggestion or submits the widget, for enter in the normal mode of vi.
+    /// nothing happens if the widget has neither
+    Submit,
+    /// shows or cycles through the suggestions of widgets with a completion
+    Complete,
+}
+
+pub fn emacs(modifier: &KeyMod, code: &KeyCode) -> Vec<Action> {
+    let action = match (modifier, code) {
+        (KeyMod::Control, KeyCode::Char('a')) => Action::Move(Motion::LineStart),
+        (KeyMod::Control, KeyCode::Char('e')) => Action::Move(Motion::LineEnd),
+        (KeyMod::Control, KeyCode::Char('b')) => Action::Move(Motion::Left),
+        (KeyMod::Control, KeyCode::Char('f')) => Action::Move(Motion::Right),
+        (KeyMod::Control, KeyCode::Char('k')) => Action::Cut(Motion::LineEnd),
+        (KeyMod::Control, KeyCode::Char('u')) => Action::Cut(Motion::LineStart),
+        (KeyMod::Control, KeyCode::Char('w')) => Action::Cut(Motion::PreviousWordStart),
+        (KeyMod::Control, KeyCode::Char('d')) => Action::Key(KeyMod::None, KeyCode::Delete),
+        (KeyMod::Control, KeyCode::Char('h')) => Action::Key(KeyMod::None, KeyCode::Backspace),
+        (KeyMod::Control, KeyCode::Char('y')) => Action::Paste {
+            after: false,
+            lines: false,
+        },
+        (KeyMod::Control, KeyCode::Char('p')) => Action::Key(KeyMod::None, KeyCode::Up),
+        (KeyMod::Control, KeyCode::Char('n')) => Action::Key(KeyMod::None, KeyCode::Down),
+        (KeyMod::Control, KeyCode::Char('_' | '/')) => Action::Undo,
+        (KeyMod::Alt, KeyCode::Char('b')) => Action::Move(Motion::PreviousWordStart),
+        (KeyMod::Alt, KeyCode::Char('f')) => Action::Move(Motion::NextWordEnd),
+        (KeyMod::Alt, KeyCode::Char('d')) => Action::Cut(Motion::NextWordEnd),
+        (KeyMod::Alt, KeyCode::Backspace) => Action::Cut(Motion::PreviousWordStart),
+        (modifier, code) => Action::Key(modifier.clone(), code.clone()),
+    };
+    vec![action]
+}
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum ViMode {
+    #[default]
+    Insert,
+    Normal,
+}
+
+/// state of the vi keymap of a widget, it starts in insert mode
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Vi {
+    pub mode: ViMode,
+    // operator waiting for its motion and the count typed in front of it
+    operator: Option<char>,
+    count: usize,
+}
+impl Vi {
+    pub fn handle(&mut self, modifier: &KeyMod, code: &KeyCode) -> Vec<Action> {
+        if self.mode == ViMode::Insert {
+            // the cursor goes back onto the last inserted grapheme
+            if *code == KeyCode::Esc {
+                self.mode = ViMode::Normal;
+                return vec![Action::Move(Motion::Left)];
+            }
+            return vec![Action::Key(modifier.clone(), code.clone())];
+        }
+
+        let c = match (modifier, code) {
+            (KeyMod::Control, KeyCode::Char('r')) => {
+                self.reset();
+                return vec![Action::Redo];
+            }
+            (KeyMod::Control, _) => return Vec::new(),
+            (_, KeyCode::Char(c)) => *c,
+            (_, KeyCode::Left | KeyCode::Backspace) => 'h',
+            (_, KeyCode::Right) => 'l',
+            (_, KeyCode::Home) => '0',
+            (_, KeyCode::End) => '$',
+            (_, KeyCode::Up) => 'k',
+            (_, KeyCode::Down) => 'j',
+            // the normal mode never inserts text
+            (_, KeyCode::Enter) => {
+                self.reset();
+                return vec![Action::Submit];
+            }
+            (_, KeyCode::Tab) => {
+                self.reset();
+                return vec![Action::Complete];
+            }
+            _ => {
+                self.reset();
+                return Vec::new();
+            }
+        };
+
+        if let Some(digit) = c.to_digit(10).filter(|d| *d > 0 || self.count > 0) {
+            self.count = self.count * 10 + digit as usize;
+            return Vec::new();
+        }
+        let count = self.count.max(1);
+
+        let motion = match c {
+            'h' => Some(Motion::Left),
+            'l' | ' ' => Some(Motion::Right),
+            '0' => Some(Motion::LineStart),
+            '^' => Some(Motion::FirstNonBlank),
+            '$' => Some(Motion::LineEnd),
+            'w' => Some(Motion::NextWordStart),
+            'b' => Some(Motion::PreviousWordStart),
+            'e' => Some(Motion::WordEnd),
+            _ => None,
+        };
+
+        // `dw`, `c$`, `yy`, ...
+        if let Some(operator) = self.operator {
+            self.reset();
+            let motion = match motion {
+                // `cw` keeps the space behind the word like `ce`
+                Some(Motion::NextWordStart) if operator == 'c' => Motion::WordEnd,
+                Some(motion) => motion,
+                None if c == operator => Motion::Line,
+                None => return Vec::new(),
+            };
+            return match operator {
+                'y' => vec![Action::Copy(motion)],
+                // `cc` keeps the empty line to type into
+                'c' if motion == Motion::Line => self.insert(vec![
+                    Action::Move(Motion::LineStart),
+                    Action::Cut(Motion::LineEnd),
+                ]),
+                'c' => self.insert(vec![Action::Cut(motion); count]),
+                _ if motion == Motion::Line => {
+                    let mut actions = vec![Action::Cut(motion); count];
+                    actions.push(Action::Move(Motion::FirstNonBlank));
+                    actions
+                }
+                _ => vec![Action::Cut(motion); count],
+            };
+        }
+        if matches!(c, 'd' | 'c' | 'y') {
+            self.operator = Some(c);
+            return Vec::new();
+        }
+
+        self.reset();
+        if let Some(motion) = motion {
+            return vec![Action::Move(motion); count];
+        }
+        match c {
+            'i' => self.insert(Vec::new()),
+            'a' => self.insert(vec![Action::Move(Motion::Right)]),
+            'I' => self.insert(vec![Action::Move(Motion::FirstNonBlank)]),
+            'A' => self.insert(vec![Action::Move(Motion::LineEnd)]),
+            'x' => vec![Action::Cut(Motion::Right); count],
+            'X' => vec![Action::Cut(Motion::Left); count],
+            'D' => vec![Action::Cut(Motion::LineEnd)],
+            'C' => self.insert(vec![Action::Cut(Motion::LineEnd)]),
+            's' => self.insert(vec![Action::Cut(Motion::Right); count]),
+            'S' => self.insert(vec![
+                Action::Move(Motion::LineStart),
+                Action::Cut(Motion::LineEnd),
+            ]),
+            'p' => vec![
+                Action::Paste {
+                    after: true,
+                    lines: true
+                };
+                count
+            ],
+            'P' => vec![
+                Action::Paste {
+                    after: false,
+                    lines: true
+                };
+                count
+            ],
+            'u' => vec![Action::Undo; count],
+            // between lines, or through the history of an input from its first and last line
+            'j' => vec![Action::Key(KeyMod::None, KeyCode::Down); count],
+            'k' => vec![Action::Key(KeyMod::None, KeyCode::Up); count],
+            _ => Vec::new(),
+        }
+    }
+
+    fn insert(&mut self, actions: Vec<Action>) -> Vec<Action> {
+        self.mode = ViMode::Insert;
+        actions
+    }
+
+    fn reset(&mut self) {
+        self.operator = None;
+        self.count = 0;
+    }
+}
+
+fn is_line_break(grapheme: &str) -> bool {
+    matches!(grapheme, "\n" | "\r\n")
+}
+
+/// index of the grapheme the cursor at `caret` moves to
+pub fn target(graphemes: &[&str], caret: usize, motion: Motion) -> usize {
+    let length = graphemes.len();
+    let class = |i: usize| text_layout::word_class(graphemes[i]);
+    let line_start = (0..caret)
+        .rev()
+        .find(|i| is_line_break(graphemes[*i]))
+        .map_or(0, |i| i + 1);
+    let line_end = (caret..length)
+        .find(|i| is_line_break(graphemes[*i]))
+        .unwrap_or(length);
+
+    match motion {
+        Motion::Left => caret.saturating_sub(1),
+        Motion::Right => (caret + 1).min(length),
+        Motion::LineStart | Motion::Line => line_start,
+        Motion::FirstNonBlank => (line_start..line_end)
+            .find(|i| class(*i) != 0)
+            .unwrap_or(line_end),
+        Motion::LineEnd => line_end,
+        Motion::NextWordStart => text_layout::next_word_start(graphemes, caret),
+        Motion::PreviousWordStart => text_layout::previous_word_start(graphemes, caret),
+        Motion::NextWordEnd => text_layout::word_end(graphemes, caret),
+        Motion::WordEnd => text_layout::word_end(graphemes, (caret + 1).min(length))
+            .saturating_sub(1)
+            .max(caret),
+    }
+}
+
+/// graphemes between the cursor and the target of the motion, which an operator acts on
+pub fn motion_range(graphemes: &[&str], caret: usize, motion: Motion) -> Range<usize> {
+    let target = target(graphemes, caret, motion);
+    match motion {
+        // the line break behind the line, or in front of it for the last line
+        Motion::Line => {
+            let end = self::target(graphemes, caret, Motion::LineEnd);
+            if end < graphemes.len() {
+                target..end + 1
+            } else {
+                target.saturating_sub(1)..end
+            }
+        }
+        Motion::WordEnd => caret..(target + 1).min(graphemes.len()),
+        _ => caret.min(target)..caret.max(target),
+    }
+}
+
+/// text copied into the clipboard by an operator, whole lines end with a line break.
+/// none if the motion does not reach any grapheme
+pub fn copied_text(graphemes: &[&str], caret: usize, motion: Motion) -> Option<String> {
+    let range = motion_range(graphemes, caret, motion);
+    if range.is_empty() {
+        return None;
+    }
+    match motion {
+        Motion::Line => {
+            let start = target(graphemes, caret, Motion::LineStart);
+            let end = target(graphemes, caret, Motion::LineEnd);
+            Some(graphemes[start..end].concat() + "\n")
+        }
+        _ => Some(graphemes[range].concat()),
+    }
+}
+
+/// index at which `text` is pasted, the pasted text and the index of the cursor afterwards
+pub fn paste_at(
+    graphemes: &[&str],
+    caret: usize,
+    text: &str,
+    after: bool,
+    lines: bool,
+) -> (usize, String, usize) {
+    let start = target(graphemes, caret, Motion::LineStart);
+    let end = target(graphemes, caret, Motion::LineEnd);
+    match text.strip_suffix('\n') {
+        // the cursor goes to the start of the pasted line
+        Some(line) if lines && after => (end, format!("\n{}", line), end + 1),
+        Some(_) if lines => (start, String::from(text), start),
+        _ => {
+            // behind the grapheme under the cursor
+            let index = if after { (caret + 1).min(end) } else { caret };
+            let length = text.graphemes(true).count();
+            (index, String::from(text), index + length)
+        }
+    }
+}
+
+/// the cursor of the normal mode is on a grapheme, not behind the end of a non-empty line
+pub fn clamp_normal_cursor(graphemes: &[&str], caret: usize) -> usize {
+    let at_line_end = graphemes.get(caret).is_none_or(|g| is_line_break(g));
+    let line_empty = caret == 0 || is_line_break(graphemes[caret - 1]);
+    if at_line_end && !line_empty {
+        caret - 1
+    } else {
+        caret
+    }
+}
+
+/// splits the room for the mode indicator of the vi keymap off the right of `area`,
+/// narrow areas leave it out
+pub(crate) fn split_mode_indicator(area: Rect) -> (Rect, Option<Rect>) {
+    if area.width < 2 * MODE_INDICATOR_WIDTH || area.height == 0 {
+        return (area, None);
+    }
+    let text = Rect {
+        width: area.width - MODE_INDICATOR_WIDTH,
+        ..area
+    };
+    let indicator = Rect {
+        x: text.right() + 1,
+        y: area.y,
+        width: MODE_INDICATOR_WIDTH - 1,
+        height: 1,
+    };
+    (text, Some(indicator))
+}
+
+/// draws `[N]` or `[I]`, in the placeholder color unless the style has a foreground color
+pub(crate) fn draw_mode_indicator(buffer: &mut Canvas, area: Rect, mode: ViMode, foreground: bool) {
+    let indicator = match mode {
+        ViMode::Normal => ["[", "N", "]"],
+        ViMode::Insert => ["[", "I", "]"],
+    };
+    text_layout::draw_graphemes(&mut buffer[area.y], area.x, area.right(), &indicator);
+    if !foreground {
+        paint(buffer, area, Some(Color::Role(Role::Placeholder)), None);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // actions of the keys typed in normal mode, `\x1b` is escape
+    fn normal(keys: &str) -> Vec<Action> {
+        let mut vi = Vi {
+            mode: ViMode::Normal,
+            ..Default::default()
+        };
+        keys.chars()
+            .flat_map(|c| {
+                let code = match c {
+                    '\x1b' => KeyCode::Esc,
+                    c => KeyCode::Char(c),
+                };
+                vi.handle(&KeyMod::None, &code)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn normal_mode_never_inserts_text() {
+        assert_eq!(normal("zqZ!"), []);
+        let mut vi = Vi {
+            mode: ViMode::Normal,
+            ..Default::default()
+        };
+        assert_eq!(vi.handle(&KeyMod::None, &KeyCode::Enter), [Action::Submit]);
+        assert_eq!(vi.handle(&KeyMod::None, &KeyCode::Tab), [Action::Complete]);
+        assert_eq!(vi.handle(&KeyMod::None, &KeyCode::Delete), []);
+        assert_eq!(vi.mode, ViMode::Normal);
+
+        // escape leaves the insert mode onto the last inserted grapheme
+        vi.mode = ViMode::Insert;
+        assert_eq!(
+            vi.handle(&KeyMod::None, &KeyCode::Char('i')),
+            [Action::Key(KeyMod::None, KeyCode::Char('i'))]
+        );
+        assert_eq!(
+            vi.handle(&KeyMod::None, &KeyCode::Esc),
+            [Action::Move(Motion::Left)]
+        );
+        assert_eq!(vi.mode, ViMode::Normal);
+    }
+
+    #[test]
+    fn counts_repeat_motions_and_operators() {
+        assert_eq!(normal("3w"), vec![Action::Move(Motion::NextWordStart); 3]);
+        assert_eq!(normal("10l").len(), 10);
+        assert_eq!(normal("0"), [Action::Move(Motion::LineStart)]);
+        assert_eq!(normal("2dw"), vec![Action::Cut(Motion::NextWordStart); 2]);
+        assert_eq!(normal("3x"), vec![Action::Cut(Motion::Right); 3]);
+        // an unknown motion cancels the operator and the count
+        assert_eq!(normal("2dzl"), [Action::Move(Motion::Right)]);
+        assert_eq!(normal("2d\x1bl"), [Action::Move(Motion::Right)]);
+    }
+
+    #[test]
+    fn operators_take_motions_or_whole_lines() {
+        assert_eq!(normal("dw"), [Action::Cut(Motion::NextWordStart)]);
+        assert_eq!(normal("d$"), [Action::Cut(Motion::LineEnd)]);
+        assert_eq!(
+            normal("dd"),
+            [
+                Action::Cut(Motion::Line),
+                Action::Move(Motion::FirstNonBlank)
+            ]
+        );
+        assert_eq!(normal("yy"), [Action::Copy(Motion::Line)]);
+        assert_eq!(normal("yb"), [Action::Copy(Motion::PreviousWordStart)]);
+
+        let mut vi = Vi {
+            mode: ViMode::Normal,
+            ..Default::default()
+        };
+        vi.handle(&KeyMod::None, &KeyCode::Char('c'));
+        assert_eq!(
+            vi.handle(&KeyMod::None, &KeyCode::Char('w')),
+            [Action::Cut(Motion::WordEnd)]
+        );
+        assert_eq!(vi.mode, ViMode::Insert);
+    }
+
+    #[test]
+    fn motions_find_their_targets() {
+        let graphemes: Vec<&str> = "foo bar\n  baz.q".graphemes(true).collect();
+        assert_eq!(target(&graphemes, 0, Motion::NextWordStart), 4);
+        assert_eq!(target(&graphemes, 4, Motion::NextWordStart), 10);
+        assert_eq!(target(&graphemes, 0, Motion::WordEnd), 2);
+        assert_eq!(target(&graphemes, 2, Motion::WordEnd), 6);
+        assert_eq!(target(&graphemes, 10, Motion::PreviousWordStart), 4);
+        assert_eq!(target(&graphemes, 12, Motion::FirstNonBlank), 10);
+        assert_eq!(target(&graphemes, 12, Motion::LineStart), 8);
+        assert_eq!(target(&graphemes, 1, Motion::LineEnd), 7);
+        assert_eq!(target(&graphemes, 0, Motion::Left), 0);
+
+        // a line is cut with its line break, the last one with the break in front of it
+        assert_eq!(motion_range(&graphemes, 2, Motion::Line), 0..8);
+        assert_eq!(motion_range(&graphemes, 12, Motion::Line), 7..15);
+        assert_eq!(
+            copied_text(&graphemes, 12, Motion::Line).as_deref(),
+            Some("  baz.q\n")
+        );
+        assert_eq!(motion_range(&graphemes, 4, Motion::WordEnd), 4..7);
+        assert_eq!(copied_text(&graphemes, 0, Motion::Left), None);
+    }
+
+    #[test]
+    fn pastes_lines_below_and_text_behind_the_cursor() {
+        let graphemes: Vec<&str> = "ab\ncd".graphemes(true).collect();
+        assert_eq!(
+            paste_at(&graphemes, 1, "xy\n", true, true),
+            (2, String::from("\nxy"), 3)
+        );
+        assert_eq!(
+            paste_at(&graphemes, 4, "xy\n", false, true),
+            (3, String::from("xy\n"), 3)
+        );
+        assert_eq!(
+            paste_at(&graphemes, 0, "xy", true, true),
+            (1, String::from("xy"), 3)
+        );
+        assert_eq!(clamp_normal_cursor(&graphemes, 2), 1);
+        assert_eq!(clamp_normal_cursor(&graphemes, 5), 4);
+        assert_eq!(clamp_normal_cursor(&graphemes, 3), 3);
+    }
+}
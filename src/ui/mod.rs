@@ -1,3 +1,14 @@
+pub mod border;
+pub mod cell;
+pub mod command_history;
+pub mod completion;
+pub mod css;
+pub mod history;
+pub mod keymap;
 pub mod screen;
 pub mod style;
+pub mod text_layout;
+pub mod theme;
+pub mod validation;
+pub mod watcher;
 pub mod widget;
@@ -1,10 +1,17 @@
+use super::cell::{blank_canvas, paint, Canvas, Cell};
+use super::css::Css;
+use super::style::{Attributes, Color, StyleSheet, Wrap};
+use super::text_layout;
+use super::theme::{Role, Theme};
+use super::watcher::CssWatcher;
 use super::widget::container::Container;
-use super::widget::Widget;
+use super::widget::{CursorShape, Popup, Widget};
 use crate::events::{Event, MouseEvent};
-use crossterm::{cursor, event, queue, terminal};
+use crossterm::{cursor, event, queue, style, terminal};
 use std::io::{stdout, Result, Stdout, Write};
 
-use crate::{Position, Size};
+use crate::{Position, Rect, Size};
+use std::path::PathBuf;
 use crate::ui::style::Style;
 
 
@@ -12,10 +19,17 @@ pub struct Screen {
     stdout: Stdout,
     pub width: usize,
     pub height: usize,
-    pub canvas: Vec<Vec<char>>,
+    pub canvas: Canvas,
     pub container: Container,
     pub messages: Vec<Vec<String>>,
-    previos_lines: Vec<String>, // for render optimisation
+    /// colors used for the roles of the widgets
+    pub theme: Theme,
+    /// stylesheet applied to the container before every draw
+    pub css: Option<Css>,
+    watcher: Option<CssWatcher>,
+    // theme from before the stylesheet was applied, its `@theme` blocks are applied on top of it
+    base_theme: Option<Theme>,
+    previos_lines: Vec<Vec<Cell>>, // for render optimisation
 }
 impl Screen{
     pub fn new(mut container: Container) -> Result<Self> {
@@ -25,10 +39,10 @@ impl Screen{
         let (cols, rows) = terminal::size()?;
 
         container.set_size(Size {
-            x: cols as f32,
-            y: rows as f32,
+            x: cols as usize,
+            y: rows as usize,
         });
-        container.set_position(Position { x: 0.0, y: 0.0 });
+        container.set_position(Position { x: 0, y: 0 });
         container.apply_style();
 
         queue!(
@@ -43,10 +57,17 @@ impl Screen{
             stdout,
             width: cols as usize,
             height: rows as usize,
-            canvas: vec![vec![' '; cols as usize]; rows as usize],
+            canvas: blank_canvas(Size {
+                x: cols as usize,
+                y: rows as usize,
+            }),
             container,
             messages: Vec::new(),
-            previos_lines: vec![String::new(); rows as usize],
+            theme: Theme::default(),
+            css: None,
+            watcher: None,
+            base_theme: None,
+            previos_lines: vec![Vec::new(); rows as usize],
         })
     }
 
@@ -64,22 +85,15 @@ impl Screen{
 
             // alway handle mouse and transform coords
             Event::Mouse(event) => {
-                let (mouse_x, mouse_y) = (event.column as u32, event.row as u32);
-                //let widget = *widget;
-                let widget_pos = self.container.get_position();
-                let widget_size = self.container.get_size();
-
-                let mouse_x = mouse_x as f32;
-                let mouse_y = mouse_y as f32;
-
-                if mouse_x <= widget_pos.x + widget_size.x
-                    && mouse_x >= widget_pos.x
-                    && mouse_y <= widget_pos.y + widget_size.y
-                    && mouse_y >= widget_pos.y
-                {
+                let (mouse_x, mouse_y) = (event.column as usize, event.row as usize);
+                let rect = self.container.get_rect();
+
+                let inside = rect.contains(mouse_x, mouse_y);
+                self.container.set_hovered(inside);
+                if inside {
                     let mut msg = self.container.handle_input_event(Event::Mouse(MouseEvent {
-                        column: (mouse_x - widget_pos.x) as u16,
-                        row: (mouse_y - widget_pos.y) as u16,
+                        column: (mouse_x - rect.x) as u16,
+                        row: (mouse_y - rect.y) as u16,
                         ..event
                     }));
 
@@ -91,33 +105,132 @@ impl Screen{
     }
 
     pub fn resize(&mut self, width: usize, height: usize) {
-        self.canvas = vec![vec![' '; width]; height];
-        self.previos_lines = vec![String::new(); height];
+        self.canvas = blank_canvas(Size {
+            x: width,
+            y: height,
+        });
+        self.previos_lines = vec![Vec::new(); height];
         self.width = width;
         self.height = height;
 
         self.container.set_size(Size {
-            x: width as f32,
-            y: height as f32,
+            x: width,
+            y: height,
         });
+        // layout again, so breakpoints of the containers get updated
+        self.container.apply_style();
     }
 
     pub fn draw(&mut self) {
+        // the container may have been replaced since the last draw, so the stylesheet is applied every time
+        if let Some(css) = self.watcher.as_mut().and_then(|watcher| watcher.poll()) {
+            self.css = Some(css);
+        }
+        if let Some(css) = self.css.take() {
+            self.style_with(&css);
+            self.css = Some(css);
+        }
         self.container.apply_style();
         // actual drawing to canvas
-        let buffer = self.container.to_char_array();
+        let buffer = self.container.to_cell_array();
         let pos = self.container.get_position();
         let size = self.container.get_size();
-        for y in 0..size.y as usize {
-            for x in 0..size.x as usize {
-                let canvas_x = x + pos.x as usize;
-                let canvas_y = y + pos.y as usize;
+        for (y, row) in buffer.iter().enumerate().take(size.y) {
+            for (x, cell) in row.iter().enumerate().take(size.x) {
+                let canvas_x = x + pos.x;
+                let canvas_y = y + pos.y;
 
                 if self.width > canvas_x && self.height > canvas_y {
-                    self.canvas[canvas_y][canvas_x] = buffer[y][x]
+                    self.canvas[canvas_y][canvas_x] = cell.clone()
                 }
             }
         }
+
+        if let Some(popup) = self.container.get_popup() {
+            self.draw_popup(popup, pos);
+        }
+
+        // errors of the watched stylesheet are shown instead of crashing
+        if let Some(error) = self.watcher.as_ref().and_then(|w| w.error.clone()) {
+            self.draw_error(&error);
+        }
+    }
+
+    // below the anchor if it fits there, otherwise above it if it fits there
+    fn draw_popup(&mut self, popup: Popup, origin: Position) {
+        let height = popup.canvas.len();
+        let width = popup.canvas.first().map_or(0, |row| row.len());
+        let anchor = Rect {
+            x: origin.x + popup.anchor.x,
+            y: origin.y + popup.anchor.y,
+            ..popup.anchor
+        };
+
+        let y = if anchor.bottom() + height > self.height && anchor.y >= height {
+            anchor.y - height
+        } else {
+            anchor.bottom()
+        };
+        let x = anchor.x.min(self.width.saturating_sub(width));
+        for (i, row) in popup.canvas.into_iter().enumerate() {
+            for (j, cell) in row.into_iter().enumerate() {
+                if x + j < self.width && y + i < self.height {
+                    self.canvas[y + i][x + j] = cell;
+                }
+            }
+        }
+    }
+
+    // overlay at the bottom of the screen
+    fn draw_error(&mut self, message: &str) {
+        let style = StyleSheet {
+            wrap: Wrap::Word,
+            foreground: Some(Color::Role(Role::Background)),
+            background: Some(Color::Role(Role::Error)),
+            ..Default::default()
+        };
+        let height = text_layout::measure(message, self.width, &style)
+            .y
+            .min(self.height);
+        let area = Rect {
+            x: 0,
+            y: self.height - height,
+            width: self.width,
+            height,
+        };
+
+        for row in self.canvas.iter_mut().skip(area.y) {
+            row.fill(Cell::blank());
+        }
+        paint(&mut self.canvas, area, style.foreground, style.background);
+        text_layout::draw_text(&mut self.canvas, area, message, &style);
+    }
+
+    /// restyles the whole tree with the colors of `theme` on the next render
+    pub fn set_theme(&mut self, theme: Theme) {
+        self.theme = theme;
+        self.base_theme = None;
+        // colors changed, so every line has to be written again
+        self.previos_lines = vec![Vec::new(); self.height];
+    }
+
+    /// styles the whole tree with the rules of `css` on every draw
+    pub fn apply_css(&mut self, css: &Css) {
+        self.css = Some(css.clone());
+        self.style_with(css);
+        self.container.apply_style();
+    }
+
+    fn style_with(&mut self, css: &Css) {
+        css.apply(&mut self.container);
+        self.theme = *self.base_theme.get_or_insert(self.theme);
+        css.apply_theme(&mut self.theme);
+    }
+
+    /// applies the stylesheet at `path` and loads it again whenever the file changes,
+    /// errors are shown at the bottom of the screen and the last valid stylesheet is kept
+    pub fn watch_css<P: Into<PathBuf>>(&mut self, path: P) {
+        self.watcher = Some(CssWatcher::new(path));
     }
 
     pub fn get_message(&mut self) -> Option<Vec<String>> {
@@ -125,23 +238,61 @@ impl Screen{
     }
 
     pub fn render(&mut self) -> Result<()> {
-        let mut lines: Vec<String> = Vec::new();
+        let mut lines: Vec<Vec<Cell>> = Vec::new();
         for row in self.canvas.iter() {
-            let mut line: String = String::new();
-            for c in row {
-                match c {
-                    '\t' | '\n' => line.push(' '), // unsupported rn
-                    c => line.push(*c),
-                }
-            }
-            lines.push(line);
+            lines.push(render_line(row, &self.theme));
         }
 
         for (row, line) in lines.iter().enumerate() {
             if line != &self.previos_lines[row] {
                 queue!(self.stdout, cursor::MoveTo(0, row as u16))?;
-                write!(self.stdout, "{}", line)?;
+                let mut colors: Option<(Color, Color)> = None;
+                let mut attributes: Option<Attributes> = None;
+                for cell in line {
+                    // resetting the attributes resets the colors too
+                    if attributes != Some(cell.attributes) {
+                        queue!(self.stdout, style::SetAttribute(style::Attribute::Reset))?;
+                        for attribute in to_crossterm_attributes(cell.attributes) {
+                            queue!(self.stdout, style::SetAttribute(attribute))?;
+                        }
+                        attributes = Some(cell.attributes);
+                        colors = None;
+                    }
+                    if colors != Some((cell.fg, cell.bg)) {
+                        queue!(
+                            self.stdout,
+                            style::SetForegroundColor(to_crossterm_color(cell.fg)),
+                            style::SetBackgroundColor(to_crossterm_color(cell.bg))
+                        )?;
+                        colors = Some((cell.fg, cell.bg));
+                    }
+                    write!(self.stdout, "{}", cell.symbol)?;
+                }
+            }
+        }
+        queue!(
+            self.stdout,
+            style::SetAttribute(style::Attribute::Reset),
+            style::ResetColor
+        )?;
+
+        // the real cursor is shown where the focused widget wants it
+        let origin = self.container.get_position();
+        match self.container.get_cursor() {
+            Some(cursor)
+                if origin.x + cursor.position.x < self.width
+                    && origin.y + cursor.position.y < self.height =>
+            {
+                let (x, y) = (origin.x + cursor.position.x, origin.y + cursor.position.y);
+                queue!(self.stdout, cursor::MoveTo(x as u16, y as u16))?;
+                write!(
+                    self.stdout,
+                    "\x1b[{} q",
+                    cursor_style_code(cursor.shape, cursor.blinking)
+                )?;
+                queue!(self.stdout, cursor::Show)?;
             }
+            _ => queue!(self.stdout, cursor::Hide)?,
         }
         self.stdout.flush()?;
         self.previos_lines = lines;
@@ -150,11 +301,16 @@ impl Screen{
     }
 
     pub fn clear(&mut self) {
-        self.canvas = vec![vec![' '; self.width]; self.height];
+        self.canvas = blank_canvas(Size {
+            x: self.width,
+            y: self.height,
+        });
     }
 
     pub fn exit(&mut self) -> Result<()> {
         terminal::disable_raw_mode()?;
+        // the default cursor style of the terminal
+        write!(self.stdout, "\x1b[0 q")?;
         queue!(
             self.stdout,
             cursor::Show,
@@ -166,3 +322,102 @@ impl Screen{
         Ok(())
     }
 }
+
+// wide characters are kept once and cover their continuation cells,
+// halves of wide characters that got separated by clipping or overlapping widgets are replaced by spaces.
+// roles of the colors are resolved with the theme
+fn render_line(row: &[Cell], theme: &Theme) -> Vec<Cell> {
+    let mut line: Vec<Cell> = Vec::new();
+    let mut covered: usize = 0;
+    for (x, cell) in row.iter().enumerate() {
+        let space = Cell::blank()
+            .with_fg(theme.resolve(cell.fg))
+            .with_bg(theme.resolve(cell.bg))
+            .with_attributes(cell.attributes);
+        if cell.is_continuation() {
+            if covered > 0 {
+                covered -= 1;
+            } else {
+                line.push(space);
+            }
+            continue;
+        }
+        covered = 0;
+
+        let width = cell.width();
+        let complete = row
+            .iter()
+            .skip(x + 1)
+            .take(width.saturating_sub(1))
+            .filter(|c| c.is_continuation())
+            .count()
+            + 1
+            == width;
+        match cell.symbol.as_str() {
+            "\t" | "\n" => line.push(space), // unsupported rn
+            _ if width == 0 => line.push(space),
+            symbol if complete => {
+                line.push(Cell {
+                    symbol: symbol.to_string(),
+                    ..space
+                });
+                covered = width - 1;
+            }
+            _ => line.push(space),
+        }
+    }
+    line
+}
+
+fn to_crossterm_color(color: Color) -> style::Color {
+    match color {
+        Color::Reset | Color::Role(_) => style::Color::Reset,
+        Color::Black => style::Color::Black,
+        Color::DarkGrey => style::Color::DarkGrey,
+        Color::Red => style::Color::Red,
+        Color::DarkRed => style::Color::DarkRed,
+        Color::Green => style::Color::Green,
+        Color::DarkGreen => style::Color::DarkGreen,
+        Color::Yellow => style::Color::Yellow,
+        Color::DarkYellow => style::Color::DarkYellow,
+        Color::Blue => style::Color::Blue,
+        Color::DarkBlue => style::Color::DarkBlue,
+        Color::Magenta => style::Color::Magenta,
+        Color::DarkMagenta => style::Color::DarkMagenta,
+        Color::Cyan => style::Color::Cyan,
+        Color::DarkCyan => style::Color::DarkCyan,
+        Color::White => style::Color::White,
+        Color::Grey => style::Color::Grey,
+        Color::Rgb { r, g, b } => style::Color::Rgb { r, g, b },
+        Color::AnsiValue(value) => style::Color::AnsiValue(value),
+    }
+}
+
+fn to_crossterm_attributes(attributes: Attributes) -> Vec<style::Attribute> {
+    [
+        (attributes.bold, style::Attribute::Bold),
+        (attributes.dim, style::Attribute::Dim),
+        (attributes.italic, style::Attribute::Italic),
+        (attributes.underlined, style::Attribute::Underlined),
+        (attributes.reversed, style::Attribute::Reverse),
+        (attributes.crossed_out, style::Attribute::CrossedOut),
+    ]
+    .iter()
+    .filter(|(set, _)| *set)
+    .map(|(_, attribute)| *attribute)
+    .collect()
+}
+
+// parameter of the DECSCUSR escape sequence
+fn cursor_style_code(shape: CursorShape, blinking: bool) -> u8 {
+    let steady = match shape {
+        CursorShape::Block => 2,
+        CursorShape::Underline => 4,
+        CursorShape::Bar => 6,
+    };
+    if blinking {
+        steady - 1
+    } else {
+        steady
+    }
+}
@@ -1,42 +1,290 @@
+use super::border::BorderType;
+use super::theme::Role;
+use super::widget::TAB_WIDTH;
+
 #[derive(Copy, Clone, Debug, PartialEq)]
 pub struct StyleSheet {
     pub width: Length,
     pub height: Length,
-    pub padding: Padding, 
+    /// space around the widget, outside of its border
+    pub margin: Margin,
+    /// space between the border and the content of the widget
+    pub padding: Padding,
+    pub placement: Placement,
+    pub z_index: i32,
+    pub text_align: TextAlign,
+    pub vertical_align: VerticalAlign,
+    pub wrap: Wrap,
+    pub overflow: Overflow,
+    /// distance between tab stops in columns
+    pub tab_width: usize,
+    /// color of the text, the widget chooses a role of the theme when not set
+    pub foreground: Option<Color>,
+    pub background: Option<Color>,
+    /// color of the border, `Role::Border` when not set
+    pub border_color: Option<Color>,
+    /// replaces the kind of the border of the widget when set
+    pub border: Option<BorderType>,
+    pub attributes: Attributes,
+    /// overrides used while the widget is in a state, applied in this order
+    pub focused: StateStyle,
+    pub hovered: StateStyle,
+    pub pressed: StateStyle,
+    pub invalid: StateStyle,
+    pub disabled: StateStyle,
+}
+impl StyleSheet {
+    /// style with the overrides of every active state applied
+    pub fn for_state(&self, state: State) -> StyleSheet {
+        let mut style = *self;
+        let overrides = [
+            (state.focused, self.focused),
+            (state.hovered, self.hovered),
+            (state.pressed, self.pressed),
+            (state.invalid, self.invalid),
+            (state.disabled, self.disabled),
+        ];
+        for (_, overrides) in overrides.iter().filter(|(active, _)| *active) {
+            style.foreground = overrides.foreground.or(style.foreground);
+            style.background = overrides.background.or(style.background);
+            style.border_color = overrides.border_color.or(style.border_color);
+            style.border = overrides.border.or(style.border);
+            style.attributes = overrides.attributes.unwrap_or(style.attributes);
+        }
+        style
+    }
 }
 impl Default for StyleSheet {
     fn default() -> Self {
         Self {
             width: Length::Relative(0.25),
             height: Length::Relative(0.25),
-            padding: Padding {
-                x_start: 1.0,
-                x_end: 0.0,
-                y_start: 1.0,
-                y_end: 0.0,
+            margin: Margin::default(),
+            padding: Padding::default(),
+            placement: Placement::default(),
+            z_index: 0,
+            text_align: TextAlign::Left,
+            vertical_align: VerticalAlign::Top,
+            wrap: Wrap::None,
+            overflow: Overflow::Clip,
+            tab_width: TAB_WIDTH,
+            foreground: None,
+            background: None,
+            border_color: None,
+            border: None,
+            attributes: Attributes::default(),
+            focused: StateStyle {
+                border_color: Some(Color::Role(Role::BorderFocused)),
+                ..Default::default()
+            },
+            hovered: StateStyle::default(),
+            pressed: StateStyle {
+                attributes: Some(Attributes {
+                    reversed: true,
+                    ..Default::default()
+                }),
+                ..Default::default()
+            },
+            invalid: StateStyle {
+                foreground: Some(Color::Role(Role::Error)),
+                border_color: Some(Color::Role(Role::Error)),
+                ..Default::default()
+            },
+            disabled: StateStyle {
+                foreground: Some(Color::Role(Role::Disabled)),
+                border_color: Some(Color::Role(Role::Disabled)),
+                ..Default::default()
             },
         }
     }
 }
 impl Eq for StyleSheet {}
 
+/// interaction state of a widget, see `StyleSheet::for_state`
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct State {
+    /// selected, for example with tab
+    pub focused: bool,
+    /// under the mouse
+    pub hovered: bool,
+    /// mouse button held down on the widget
+    pub pressed: bool,
+    /// text that was not accepted by the validation of an input
+    pub invalid: bool,
+    /// ignores input
+    pub disabled: bool,
+}
+
+/// changes to a `StyleSheet` while the widget is in a state, `None` keeps the value of the normal style
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct StateStyle {
+    pub foreground: Option<Color>,
+    pub background: Option<Color>,
+    pub border_color: Option<Color>,
+    /// symbols of the border, the space it takes does not depend on the state
+    pub border: Option<BorderType>,
+    pub attributes: Option<Attributes>,
+}
+
+/// text attributes of a cell, support depends on the terminal
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Attributes {
+    pub bold: bool,
+    pub dim: bool,
+    pub italic: bool,
+    pub underlined: bool,
+    pub reversed: bool,
+    pub crossed_out: bool,
+}
 
+/// absolute lengths are in cells, relative lengths are a fraction of the available space
+///
+/// both may be fractional, layout rounds them to whole cells
 #[derive(Copy, Clone, Debug, PartialEq)]
 pub enum Length {
     Absolute(f32),
     Relative(f32),
+    /// size measured from the content of the widget
+    Auto,
+}
+impl Length {
+    /// `Auto` can only be measured by the widget and takes all of the available space here
+    pub fn resolve(&self, available: f32) -> f32 {
+        match self {
+            Length::Absolute(x) => *x,
+            Length::Relative(x) => available * x,
+            Length::Auto => available,
+        }
+    }
 }
 
-#[derive(Copy, Clone, Debug, PartialEq)]
-pub struct Padding {
-    pub x_start: f32,
-    pub x_end: f32,
-    pub y_start: f32,
-    pub y_end: f32,
+/// size of each side of a margin, border or padding
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Spacing {
+    pub x_start: usize,
+    pub x_end: usize,
+    pub y_start: usize,
+    pub y_end: usize,
+}
+impl Spacing {
+    pub fn uniform(size: usize) -> Self {
+        Self {
+            x_start: size,
+            x_end: size,
+            y_start: size,
+            y_end: size,
+        }
+    }
+
+    pub fn horizontal(&self) -> usize {
+        self.x_start + self.x_end
+    }
+
+    pub fn vertical(&self) -> usize {
+        self.y_start + self.y_end
+    }
+}
+
+pub type Margin = Spacing;
+pub type Padding = Spacing;
+
+/// where a widget is positioned inside of a `ContainerKind::Stack`
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Anchor {
+    /// offset from the left or upper edge
+    Start(usize),
+    /// offset from the right or bottom edge
+    End(usize),
+    Center,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Placement {
+    pub x: Anchor,
+    pub y: Anchor,
+}
+impl Default for Placement {
+    fn default() -> Self {
+        Self {
+            x: Anchor::Start(0),
+            y: Anchor::Start(0),
+        }
+    }
+}
+
+/// horizontal alignment of each line of text
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum TextAlign {
+    Left,
+    Center,
+    Right,
+    /// stretches the spaces between words so the line fills the whole width,
+    /// except for the last line
+    Justify,
+}
+
+/// vertical alignment of the whole text
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum VerticalAlign {
+    Top,
+    Middle,
+    Bottom,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Color {
+    /// default color of the terminal
+    Reset,
+    Black,
+    DarkGrey,
+    Red,
+    DarkRed,
+    Green,
+    DarkGreen,
+    Yellow,
+    DarkYellow,
+    Blue,
+    DarkBlue,
+    Magenta,
+    DarkMagenta,
+    Cyan,
+    DarkCyan,
+    White,
+    Grey,
+    Rgb { r: u8, g: u8, b: u8 },
+    AnsiValue(u8),
+    /// color of the role in the theme used by the `Screen`
+    Role(Role),
+}
+
+/// how text is broken into lines that fit the width of the widget
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Wrap {
+    /// lines are only broken at newlines
+    None,
+    /// lines are broken at the last character that fits
+    Character,
+    /// lines are broken between words, words longer than the width are broken at characters
+    Word,
+}
+
+/// how text that does not fit into the widget is cut off
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Overflow {
+    Clip,
+    /// replaces the end of the text with `…`
+    Ellipsis,
+    /// replaces the middle of lines with `…`, useful for file paths
+    EllipsisMiddle,
 }
 
 pub trait Style {
     fn get_style(&self) -> StyleSheet;
+    /// also makes `style` the base that stylesheets cascade from
     fn set_style(&mut self, style: StyleSheet);
     fn apply_style(&mut self);
+
+    /// style from before a `Css` stylesheet was applied, none if no stylesheet was applied yet
+    fn get_base_style(&self) -> Option<StyleSheet>;
+    fn set_base_style(&mut self, style: Option<StyleSheet>);
 }
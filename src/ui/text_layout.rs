@@ -0,0 +1,583 @@
+use super::cell::{Canvas, Cell};
+use super::style::{Overflow, StyleSheet, TextAlign, VerticalAlign, Wrap};
+use crate::{Rect, Size};
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
+
+/// single line of laid out text
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Line {
+    pub graphemes: Vec<String>,
+    /// last line before a newline or the end of the text, which does not get justified
+    pub paragraph_end: bool,
+}
+
+/// number of terminal columns a grapheme cluster occupies
+pub fn grapheme_width(grapheme: &str) -> usize {
+    grapheme.width()
+}
+
+/// number of terminal columns the graphemes occupy
+pub fn line_width<S: AsRef<str>>(graphemes: &[S]) -> usize {
+    graphemes.iter().map(|g| grapheme_width(g.as_ref())).sum()
+}
+
+/// number of terminal columns of the widest line of the text
+pub fn text_width(text: &str, tab_width: usize) -> usize {
+    split_lines(text, tab_width)
+        .iter()
+        .map(|line| line_width(line))
+        .max()
+        .unwrap_or(0)
+}
+
+/// splits text into lines of grapheme clusters,
+/// tabs are expanded to spaces up to the next multiple of `tab_width` columns
+pub fn split_lines(text: &str, tab_width: usize) -> Vec<Vec<String>> {
+    text.split('\n')
+        .map(|line| {
+            let mut graphemes: Vec<String> = Vec::new();
+            let mut column: usize = 0;
+            for grapheme in line.graphemes(true) {
+                match grapheme {
+                    "\t" => {
+                        let spaces = tab_stop(column, tab_width) - column;
+                        graphemes.extend(vec![String::from(" "); spaces]);
+                        column += spaces;
+                    }
+                    // carriage returns of windows line endings
+                    "\r" => (),
+                    grapheme => {
+                        graphemes.push(grapheme.to_string());
+                        column += grapheme_width(grapheme);
+                    }
+                }
+            }
+            graphemes
+        })
+        .collect()
+}
+
+/// class of a grapheme for moving by words: 0 for whitespace, 1 for word characters and 2 for punctuation
+pub fn word_class(grapheme: &str) -> u8 {
+    match grapheme.chars().next() {
+        Some(c) if c.is_whitespace() => 0,
+        Some(c) if c.is_alphanumeric() || c == '_' => 1,
+        _ => 2,
+    }
+}
+
+/// start of the word in front of `index`, whitespace in between is skipped
+pub fn previous_word_start<S: AsRef<str>>(graphemes: &[S], mut index: usize) -> usize {
+    let class = |i: usize| word_class(graphemes[i].as_ref());
+    while index > 0 && class(index - 1) == 0 {
+        index -= 1;
+    }
+    if let Some(word) = index.checked_sub(1).map(class) {
+        while index > 0 && class(index - 1) == word {
+            index -= 1;
+        }
+    }
+    index
+}
+
+/// start of the word behind the one under `index`, whitespace in between is skipped
+pub fn next_word_start<S: AsRef<str>>(graphemes: &[S], mut index: usize) -> usize {
+    let class = |i: usize| word_class(graphemes[i].as_ref());
+    let length = graphemes.len();
+    if let Some(word) = (index < length).then(|| class(index)).filter(|c| *c != 0) {
+        while index < length && class(index) == word {
+            index += 1;
+        }
+    }
+    while index < length && class(index) == 0 {
+        index += 1;
+    }
+    index
+}
+
+/// behind the end of the word under or after `index`
+pub fn word_end<S: AsRef<str>>(graphemes: &[S], mut index: usize) -> usize {
+    let class = |i: usize| word_class(graphemes[i].as_ref());
+    let length = graphemes.len();
+    while index < length && class(index) == 0 {
+        index += 1;
+    }
+    if let Some(word) = (index < length).then(|| class(index)) {
+        while index < length && class(index) == word {
+            index += 1;
+        }
+    }
+    index
+}
+
+/// grapheme as it is shown, `x` and `width` are display columns
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct GraphemeCell<'a> {
+    pub grapheme: &'a str,
+    pub line: usize,
+    pub x: usize,
+    pub width: usize,
+}
+
+/// every grapheme of the text as it is shown without wrapping, tabs reach to the next tab stop
+/// and line breaks have no width. `hidden` shows the graphemes as `*`, for passwords
+pub fn grapheme_cells(text: &str, tab_width: usize, hidden: bool) -> Vec<GraphemeCell<'_>> {
+    let mut cells = Vec::new();
+    let (mut line, mut x) = (0, 0);
+    for grapheme in text.graphemes(true) {
+        let (grapheme, width) = match grapheme {
+            "\n" | "\r\n" => {
+                cells.push(GraphemeCell {
+                    grapheme,
+                    line,
+                    x,
+                    width: 0,
+                });
+                line += 1;
+                x = 0;
+                continue;
+            }
+            "\t" => (grapheme, tab_stop(x, tab_width) - x),
+            "\r" => (grapheme, 0),
+            _ if hidden => ("*", 1),
+            grapheme => (grapheme, grapheme_width(grapheme)),
+        };
+        cells.push(GraphemeCell {
+            grapheme,
+            line,
+            x,
+            width,
+        });
+        x += width;
+    }
+    cells
+}
+
+/// draws the cells of `line` scrolled by `scroll` columns into `row` between `left` and `right`,
+/// graphemes that do not fit completely are not drawn and tabs are blank
+pub fn draw_cells(
+    row: &mut [Cell],
+    cells: &[GraphemeCell],
+    line: usize,
+    scroll: usize,
+    left: usize,
+    right: usize,
+) {
+    let width = right.saturating_sub(left);
+    for cell in cells.iter().filter(|cell| cell.line == line) {
+        if cell.width == 0
+            || cell.grapheme == "\t"
+            || cell.x < scroll
+            || cell.x + cell.width > scroll + width
+        {
+            continue;
+        }
+        draw_graphemes(row, left + cell.x - scroll, right, &[cell.grapheme]);
+    }
+}
+
+/// column of the next tab stop after `column`
+pub fn tab_stop(column: usize, tab_width: usize) -> usize {
+    // tabs are removed without a width
+    column
+        .checked_div(tab_width)
+        .map_or(column, |stops| (stops + 1) * tab_width)
+}
+
+/// breaks the text into lines that fit into `width` using the wrapping mode of the style
+pub fn layout(text: &str, width: usize, style: &StyleSheet) -> Vec<Line> {
+    let mut lines: Vec<Line> = Vec::new();
+    for paragraph in split_lines(text, style.tab_width) {
+        let mut wrapped = match style.wrap {
+            // nothing can fit into no width
+            _ if width == 0 => vec![paragraph],
+            Wrap::None => vec![paragraph],
+            Wrap::Character => wrap_graphemes(&paragraph, width),
+            Wrap::Word => wrap_words(&paragraph, width),
+        };
+        if wrapped.is_empty() {
+            wrapped.push(Vec::new());
+        }
+
+        let count = wrapped.len();
+        lines.extend(
+            wrapped
+                .into_iter()
+                .enumerate()
+                .map(|(i, graphemes)| Line {
+                    graphemes,
+                    paragraph_end: i + 1 == count,
+                }),
+        );
+    }
+    lines
+}
+
+/// size of the laid out text, the width may exceed `width` when it is not wrapped
+pub fn measure(text: &str, width: usize, style: &StyleSheet) -> Size {
+    let lines = layout(text, width, style);
+    Size {
+        x: lines
+            .iter()
+            .map(|l| line_width(&l.graphemes))
+            .max()
+            .unwrap_or(0),
+        y: lines.len(),
+    }
+}
+
+/// lays out the text and draws it into `area` of the buffer
+pub fn draw_text(buffer: &mut Canvas, area: Rect, text: &str, style: &StyleSheet) {
+    let lines = layout(text, area.width, style);
+    draw_lines(buffer, area, &lines, style);
+}
+
+/// draws the lines into `area` of the buffer aligned by the style,
+/// anything outside of it is clipped or cut off with an ellipsis
+pub fn draw_lines(buffer: &mut Canvas, area: Rect, lines: &[Line], style: &StyleSheet) {
+    let y_offset = match style.vertical_align {
+        VerticalAlign::Top => 0,
+        VerticalAlign::Middle => area.height.saturating_sub(lines.len()) / 2,
+        VerticalAlign::Bottom => area.height.saturating_sub(lines.len()),
+    };
+    let visible_lines = area.height.min(lines.len());
+
+    for (i, line) in lines.iter().take(visible_lines).enumerate() {
+        let y = area.y + y_offset + i;
+        if y >= buffer.len() {
+            break;
+        }
+
+        let mut graphemes = if style.text_align == TextAlign::Justify && !line.paragraph_end {
+            justify(&line.graphemes, area.width)
+        } else {
+            line.graphemes.clone()
+        };
+
+        // the last visible line shows that there are more lines below
+        let cut_off = i + 1 == visible_lines && lines.len() > visible_lines;
+        graphemes = match style.overflow {
+            Overflow::Clip => graphemes,
+            Overflow::Ellipsis => ellipsis_end(graphemes, area.width, cut_off),
+            Overflow::EllipsisMiddle if cut_off => ellipsis_end(graphemes, area.width, true),
+            Overflow::EllipsisMiddle => ellipsis_middle(graphemes, area.width),
+        };
+
+        let width = line_width(&graphemes);
+        let x_offset = match style.text_align {
+            TextAlign::Left | TextAlign::Justify => 0,
+            TextAlign::Center => area.width.saturating_sub(width) / 2,
+            TextAlign::Right => area.width.saturating_sub(width),
+        };
+
+        let right = area.right().min(buffer[y].len());
+        draw_graphemes(&mut buffer[y], area.x + x_offset, right, &graphemes);
+    }
+}
+
+/// draws graphemes into the row starting at column `x`, wide characters are followed by a
+/// continuation cell and are left out when they do not fit in front of `right` or the row end
+pub fn draw_graphemes<S: AsRef<str>>(row: &mut [Cell], mut x: usize, right: usize, graphemes: &[S]) {
+    let right = right.min(row.len());
+    for grapheme in graphemes {
+        let grapheme = grapheme.as_ref();
+        let width = grapheme_width(grapheme);
+        if width == 0 {
+            continue;
+        }
+        if x + width > right {
+            break;
+        }
+
+        // colors of the cells are kept
+        row[x].symbol = grapheme.to_string();
+        for cell in row.iter_mut().skip(x + 1).take(width - 1) {
+            cell.symbol.clear();
+        }
+        x += width;
+    }
+}
+
+// breaks lines at the last grapheme that fits
+fn wrap_graphemes(paragraph: &[String], width: usize) -> Vec<Vec<String>> {
+    let mut lines: Vec<Vec<String>> = Vec::new();
+    let mut line: Vec<String> = Vec::new();
+    let mut line_length: usize = 0;
+    for grapheme in paragraph {
+        let grapheme_length = grapheme_width(grapheme);
+        if line_length + grapheme_length > width && !line.is_empty() {
+            lines.push(std::mem::take(&mut line));
+            line_length = 0;
+        }
+        line.push(grapheme.clone());
+        line_length += grapheme_length;
+    }
+    lines.push(line);
+    lines
+}
+
+// greedily fills each line with as many words as fit
+fn wrap_words(paragraph: &[String], width: usize) -> Vec<Vec<String>> {
+    let mut lines: Vec<Vec<String>> = Vec::new();
+    let mut line: Vec<String> = Vec::new();
+    for word in paragraph.split(|g| g == " ") {
+        let word_width = line_width(word);
+        let needed = if line.is_empty() {
+            word_width
+        } else {
+            line_width(&line) + 1 + word_width
+        };
+        if needed <= width {
+            if !line.is_empty() {
+                line.push(String::from(" "));
+            }
+            line.extend_from_slice(word);
+            continue;
+        }
+
+        if !line.is_empty() {
+            lines.push(line);
+        }
+        // words longer than the line are broken at graphemes
+        let mut broken = wrap_graphemes(word, width);
+        line = broken.pop().unwrap_or_default();
+        lines.append(&mut broken);
+    }
+    lines.push(line);
+    lines
+}
+
+// distributes the missing width between the words, the leftmost gaps get the remainder
+fn justify(line: &[String], width: usize) -> Vec<String> {
+    let words: Vec<&[String]> = line.split(|g| g == " ").filter(|w| !w.is_empty()).collect();
+    let letters: usize = words.iter().map(|w| line_width(w)).sum();
+    if words.len() < 2 || letters >= width {
+        return line.to_vec();
+    }
+
+    let gaps = words.len() - 1;
+    let spaces = width - letters;
+    let mut justified: Vec<String> = Vec::with_capacity(width);
+    for (i, word) in words.iter().enumerate() {
+        justified.extend_from_slice(word);
+        if i < gaps {
+            let gap = spaces / gaps + usize::from(i < spaces % gaps);
+            justified.extend(std::iter::repeat_n(String::from(" "), gap));
+        }
+    }
+    justified
+}
+
+// removes graphemes from the end until the line is at most `width` columns wide
+fn truncate(graphemes: &mut Vec<String>, width: usize) {
+    while line_width(graphemes) > width {
+        graphemes.pop();
+    }
+}
+
+// cuts the end of the line off when it does not fit or when `force`d
+fn ellipsis_end(mut graphemes: Vec<String>, width: usize, force: bool) -> Vec<String> {
+    if width == 0 || (line_width(&graphemes) <= width && !force) {
+        return graphemes;
+    }
+    // trailing spaces would separate the ellipsis from the text
+    while graphemes.last().is_some_and(|g| g == " ") {
+        graphemes.pop();
+    }
+    truncate(&mut graphemes, width - 1);
+    graphemes.push(String::from("…"));
+    graphemes
+}
+
+// keeps the start and the end of the line, which both are the most useful parts of paths
+fn ellipsis_middle(graphemes: Vec<String>, width: usize) -> Vec<String> {
+    if width == 0 || line_width(&graphemes) <= width {
+        return graphemes;
+    }
+    let end_width = (width - 1) / 2;
+    let start_width = width - 1 - end_width;
+
+    let mut start: Vec<String> = graphemes.clone();
+    truncate(&mut start, start_width);
+
+    let mut end: Vec<String> = graphemes.into_iter().rev().collect();
+    truncate(&mut end, end_width);
+    end.reverse();
+
+    start.push(String::from("…"));
+    start.append(&mut end);
+    start
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ui::cell::blank_canvas;
+
+    fn aligned(text_align: TextAlign, vertical_align: VerticalAlign) -> StyleSheet {
+        StyleSheet {
+            text_align,
+            vertical_align,
+            ..StyleSheet::default()
+        }
+    }
+
+    fn style(wrap: Wrap, overflow: Overflow) -> StyleSheet {
+        StyleSheet {
+            wrap,
+            overflow,
+            ..StyleSheet::default()
+        }
+    }
+
+    fn texts(lines: &[Line]) -> Vec<String> {
+        lines.iter().map(|l| l.graphemes.concat()).collect()
+    }
+
+    // draws the text into a buffer of the given size and returns its rows
+    fn draw(text: &str, width: usize, height: usize, style: &StyleSheet) -> Vec<String> {
+        let mut buffer = blank_canvas(Size {
+            x: width,
+            y: height,
+        });
+        let area = Rect {
+            x: 0,
+            y: 0,
+            width,
+            height,
+        };
+        draw_text(&mut buffer, area, text, style);
+        buffer
+            .iter()
+            .map(|row| row.iter().map(|c| c.symbol.as_str()).collect())
+            .collect()
+    }
+
+    #[test]
+    fn split_lines_expands_tabs_to_tab_stops() {
+        let lines = split_lines("a\tb\n\tc\nabcd\te", 4);
+        assert_eq!(lines[0].concat(), "a   b");
+        assert_eq!(lines[1].concat(), "    c");
+        assert_eq!(lines[2].concat(), "abcd    e");
+        assert_eq!(split_lines("漢\tb", 4)[0].concat(), "漢  b");
+        assert_eq!(text_width("a\tb", 8), 9);
+        assert_eq!(tab_stop(3, 0), 3);
+    }
+
+    #[test]
+    fn aligns_horizontally_and_vertically() {
+        let center = aligned(TextAlign::Center, VerticalAlign::Middle);
+        assert_eq!(draw("ab", 6, 3, &center), ["      ", "  ab  ", "      "]);
+        let right = aligned(TextAlign::Right, VerticalAlign::Bottom);
+        assert_eq!(draw("ab\nc", 4, 3, &right), ["    ", "  ab", "   c"]);
+    }
+
+    #[test]
+    fn justify_spreads_the_gaps() {
+        assert_eq!(justify(&split_lines("a b c", 4)[0], 8).concat(), "a   b  c");
+        // the last line of a paragraph is not justified
+        let justified = StyleSheet {
+            wrap: Wrap::Word,
+            ..aligned(TextAlign::Justify, VerticalAlign::Top)
+        };
+        assert_eq!(draw("aa b cc", 5, 2, &justified), ["aa  b", "cc   "]);
+    }
+
+    #[test]
+    fn wraps_words_and_breaks_long_words() {
+        let lines = layout("hello big world", 9, &style(Wrap::Word, Overflow::Clip));
+        assert_eq!(texts(&lines), ["hello big", "world"]);
+        assert!(!lines[0].paragraph_end && lines[1].paragraph_end);
+
+        let lines = layout("abcdefghij xy", 4, &style(Wrap::Word, Overflow::Clip));
+        assert_eq!(texts(&lines), ["abcd", "efgh", "ij", "xy"]);
+
+        let lines = layout("abcdefg", 3, &style(Wrap::Character, Overflow::Clip));
+        assert_eq!(texts(&lines), ["abc", "def", "g"]);
+    }
+
+    #[test]
+    fn keeps_lines_unwrapped_without_wrap_or_width() {
+        let none = style(Wrap::None, Overflow::Clip);
+        assert_eq!(texts(&layout("a b c\n", 1, &none)), ["a b c", ""]);
+        let word = style(Wrap::Word, Overflow::Clip);
+        assert_eq!(texts(&layout("a b c", 0, &word)), ["a b c"]);
+        assert_eq!(measure("ab\ncde", 10, &none), Size { x: 3, y: 2 });
+        assert_eq!(measure("ab cde", 3, &word), Size { x: 3, y: 2 });
+    }
+
+    #[test]
+    fn ellipsis_cuts_off_the_end() {
+        let ellipsis = style(Wrap::None, Overflow::Ellipsis);
+        assert_eq!(draw("hello world", 8, 1, &ellipsis), ["hello w…"]);
+        assert_eq!(draw("hello", 8, 1, &ellipsis), ["hello   "]);
+        // trailing spaces of lines cut off below are removed before the ellipsis
+        assert_eq!(draw("hello   \nworld", 8, 1, &ellipsis), ["hello…  "]);
+    }
+
+    #[test]
+    fn ellipsis_middle_keeps_start_and_end() {
+        let middle = style(Wrap::None, Overflow::EllipsisMiddle);
+        assert_eq!(draw("/usr/local/bin", 7, 1, &middle), ["/us…bin"]);
+        assert_eq!(draw("/usr", 7, 1, &middle), ["/usr   "]);
+    }
+
+    #[test]
+    fn ellipsis_marks_lines_cut_off_below() {
+        let ellipsis = style(Wrap::Word, Overflow::Ellipsis);
+        assert_eq!(draw("one two", 5, 1, &ellipsis), ["one… "]);
+        let clip = style(Wrap::Word, Overflow::Clip);
+        assert_eq!(draw("one two", 5, 1, &clip), ["one  "]);
+    }
+
+    #[test]
+    fn measures_graphemes_by_display_width() {
+        assert_eq!(text_width("e\u{301}漢\nab", 4), 3);
+        assert_eq!(split_lines("a\r\nb", 4)[0], ["a"]);
+        let lines = layout("ab漢字c", 3, &style(Wrap::Character, Overflow::Clip));
+        assert_eq!(texts(&lines), ["ab", "漢", "字c"]);
+    }
+
+    #[test]
+    fn draw_graphemes_leaves_out_wide_graphemes_that_do_not_fit() {
+        let mut row = vec![Cell::blank(); 3];
+        draw_graphemes(&mut row, 1, 3, &["漢", "字"]);
+        let symbols: Vec<&str> = row.iter().map(|c| c.symbol.as_str()).collect();
+        assert_eq!(symbols, [" ", "漢", ""]);
+    }
+
+    #[test]
+    fn draw_graphemes_stops_at_the_end_of_the_row() {
+        let mut row = vec![Cell::blank(); 3];
+        draw_graphemes(&mut row, 1, 10, &["a", "b", "c"]);
+        let symbols: Vec<&str> = row.iter().map(|c| c.symbol.as_str()).collect();
+        assert_eq!(symbols, [" ", "a", "b"]);
+    }
+
+    #[test]
+    fn grapheme_cells_measure_tabs_and_line_breaks() {
+        let cells = grapheme_cells("a\t漢\r\nb", 4, false);
+        let positions: Vec<(usize, usize, usize)> =
+            cells.iter().map(|c| (c.line, c.x, c.width)).collect();
+        assert_eq!(
+            positions,
+            [(0, 0, 1), (0, 1, 3), (0, 4, 2), (0, 6, 0), (1, 0, 1)]
+        );
+
+        let hidden = grapheme_cells("漢b", 4, true);
+        assert!(hidden.iter().all(|c| c.grapheme == "*" && c.width == 1));
+    }
+
+    #[test]
+    fn moves_by_words() {
+        let graphemes: Vec<&str> = "foo bar.baz  qux".graphemes(true).collect();
+        assert_eq!(next_word_start(&graphemes, 0), 4);
+        assert_eq!(next_word_start(&graphemes, 4), 7);
+        assert_eq!(next_word_start(&graphemes, 11), 13);
+        assert_eq!(previous_word_start(&graphemes, 13), 8);
+        assert_eq!(previous_word_start(&graphemes, 4), 0);
+        assert_eq!(word_end(&graphemes, 3), 7);
+        assert_eq!(word_end(&graphemes, 16), 16);
+    }
+}
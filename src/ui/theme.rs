@@ -0,0 +1,156 @@
+use super::style::Color;
+
+/// semantic color roles, widgets use them by default so that they follow the theme of the `Screen`
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Role {
+    Foreground,
+    Background,
+    Primary,
+    Accent,
+    Success,
+    Warning,
+    Error,
+    Border,
+    BorderFocused,
+    Selection,
+    Placeholder,
+    Disabled,
+}
+
+/// colors of every `Role`
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Theme {
+    pub foreground: Color,
+    pub background: Color,
+    pub primary: Color,
+    pub accent: Color,
+    pub success: Color,
+    pub warning: Color,
+    pub error: Color,
+    pub border: Color,
+    pub border_focused: Color,
+    pub selection: Color,
+    pub placeholder: Color,
+    pub disabled: Color,
+}
+impl Theme {
+    /// the default colors of the terminal for every role
+    pub fn terminal() -> Self {
+        Self {
+            foreground: Color::Reset,
+            background: Color::Reset,
+            primary: Color::Reset,
+            accent: Color::Reset,
+            success: Color::Reset,
+            warning: Color::Reset,
+            error: Color::Reset,
+            border: Color::Reset,
+            border_focused: Color::Reset,
+            selection: Color::Reset,
+            placeholder: Color::Reset,
+            disabled: Color::Reset,
+        }
+    }
+
+    pub fn dark() -> Self {
+        Self {
+            foreground: Color::Grey,
+            background: Color::Black,
+            primary: Color::Blue,
+            accent: Color::Cyan,
+            success: Color::Green,
+            warning: Color::Yellow,
+            error: Color::Red,
+            border: Color::DarkGrey,
+            border_focused: Color::Cyan,
+            selection: Color::DarkBlue,
+            placeholder: Color::DarkGrey,
+            disabled: Color::DarkGrey,
+        }
+    }
+
+    pub fn light() -> Self {
+        Self {
+            foreground: Color::Black,
+            background: Color::White,
+            primary: Color::DarkBlue,
+            accent: Color::DarkMagenta,
+            success: Color::DarkGreen,
+            warning: Color::DarkYellow,
+            error: Color::DarkRed,
+            border: Color::Grey,
+            border_focused: Color::DarkBlue,
+            selection: Color::Cyan,
+            placeholder: Color::DarkGrey,
+            disabled: Color::Grey,
+        }
+    }
+
+    pub fn color(&self, role: Role) -> Color {
+        match role {
+            Role::Foreground => self.foreground,
+            Role::Background => self.background,
+            Role::Primary => self.primary,
+            Role::Accent => self.accent,
+            Role::Success => self.success,
+            Role::Warning => self.warning,
+            Role::Error => self.error,
+            Role::Border => self.border,
+            Role::BorderFocused => self.border_focused,
+            Role::Selection => self.selection,
+            Role::Placeholder => self.placeholder,
+            Role::Disabled => self.disabled,
+        }
+    }
+
+    pub fn set_color(&mut self, role: Role, color: Color) {
+        let field = match role {
+            Role::Foreground => &mut self.foreground,
+            Role::Background => &mut self.background,
+            Role::Primary => &mut self.primary,
+            Role::Accent => &mut self.accent,
+            Role::Success => &mut self.success,
+            Role::Warning => &mut self.warning,
+            Role::Error => &mut self.error,
+            Role::Border => &mut self.border,
+            Role::BorderFocused => &mut self.border_focused,
+            Role::Selection => &mut self.selection,
+            Role::Placeholder => &mut self.placeholder,
+            Role::Disabled => &mut self.disabled,
+        };
+        *field = color;
+    }
+
+    /// replaces roles by the color of this theme, roles of a theme pointing to roles end at `Reset`
+    pub fn resolve(&self, color: Color) -> Color {
+        match color {
+            Color::Role(role) => match self.color(role) {
+                Color::Role(_) => Color::Reset,
+                color => color,
+            },
+            color => color,
+        }
+    }
+}
+impl Default for Theme {
+    fn default() -> Self {
+        Self::terminal()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_theme_keeps_the_terminal_colors() {
+        let theme = Theme::default();
+        assert_eq!(theme.resolve(Color::Role(Role::Accent)), Color::Reset);
+        assert_eq!(theme.resolve(Color::Role(Role::Border)), Color::Reset);
+        assert_eq!(theme.resolve(Color::Red), Color::Red);
+        assert_ne!(
+            Theme::dark().resolve(Color::Role(Role::Accent)),
+            Color::Reset
+        );
+    }
+}
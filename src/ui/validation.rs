@@ -0,0 +1,151 @@
+use std::fmt;
+use std::sync::Arc;
+use unicode_segmentation::UnicodeSegmentation;
+
+/// decides which text an input accepts, partial text while typing has to be accepted as well
+#[derive(Clone)]
+pub enum Validator {
+    /// digits with an optional leading minus and one decimal point
+    Numeric,
+    /// every character is in one of the ranges, see `Validator::chars`
+    Chars {
+        ranges: Vec<(char, char)>,
+        negated: bool,
+    },
+    Custom(Arc<dyn Fn(&str) -> bool + Send + Sync>),
+}
+impl Validator {
+    /// character class like in a regex without the brackets, for example `a-zA-Z0-9_`.
+    /// a leading `^` negates it and `\` escapes the next character
+    pub fn chars(class: &str) -> Self {
+        let (negated, class) = match class.strip_prefix('^') {
+            Some(class) => (true, class),
+            None => (false, class),
+        };
+
+        let mut chars: Vec<(char, bool)> = Vec::new();
+        let mut escaped = false;
+        for c in class.chars() {
+            match c {
+                '\\' if !escaped => escaped = true,
+                c => {
+                    chars.push((c, escaped));
+                    escaped = false;
+                }
+            }
+        }
+
+        // an unescaped `-` between two characters makes a range
+        let mut ranges: Vec<(char, char)> = Vec::new();
+        let mut i = 0;
+        while i < chars.len() {
+            match (chars.get(i + 1), chars.get(i + 2)) {
+                (Some(('-', false)), Some((end, _))) => {
+                    ranges.push((chars[i].0, *end));
+                    i += 3;
+                }
+                _ => {
+                    ranges.push((chars[i].0, chars[i].0));
+                    i += 1;
+                }
+            }
+        }
+        Validator::Chars { ranges, negated }
+    }
+
+    pub fn custom<F: Fn(&str) -> bool + Send + Sync + 'static>(validate: F) -> Self {
+        Validator::Custom(Arc::new(validate))
+    }
+
+    pub fn validate(&self, text: &str) -> bool {
+        match self {
+            Validator::Numeric => {
+                let digits = text.strip_prefix('-').unwrap_or(text);
+                digits.matches('.').count() <= 1
+                    && digits.chars().all(|c| c.is_ascii_digit() || c == '.')
+            }
+            Validator::Chars { ranges, negated } => text.chars().all(|c| {
+                ranges
+                    .iter()
+                    .any(|(start, end)| (*start..=*end).contains(&c))
+                    != *negated
+            }),
+            Validator::Custom(validate) => validate(text),
+        }
+    }
+}
+impl fmt::Debug for Validator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Validator::Numeric => write!(f, "Numeric"),
+            Validator::Chars { ranges, negated } => f
+                .debug_struct("Chars")
+                .field("ranges", ranges)
+                .field("negated", negated)
+                .finish(),
+            Validator::Custom(_) => write!(f, "Custom(..)"),
+        }
+    }
+}
+// closures are only equal to themselves
+impl PartialEq for Validator {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Validator::Numeric, Validator::Numeric) => true,
+            (
+                Validator::Chars { ranges, negated },
+                Validator::Chars {
+                    ranges: other_ranges,
+                    negated: other_negated,
+                },
+            ) => ranges == other_ranges && negated == other_negated,
+            (Validator::Custom(validate), Validator::Custom(other)) => Arc::ptr_eq(validate, other),
+            _ => false,
+        }
+    }
+}
+impl Eq for Validator {}
+
+/// what happens to an edit that makes the text invalid
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum InvalidInput {
+    /// the edit is undone right away
+    #[default]
+    Reject,
+    /// the edit is kept and the widget is shown in the invalid state until the text is valid again
+    Flag,
+}
+
+/// placeholder in a mask that is filled by typing, every other character of a mask is inserted as is
+pub const MASK_SLOT: char = '_';
+
+/// whether the text fits into the mask as far as it goes, every grapheme takes one place of the mask.
+/// `Some` with the graphemes typed into the slots if it does
+pub fn fit_mask(text: &str, mask: &str) -> Option<String> {
+    let slot = MASK_SLOT.to_string();
+    let mut mask = mask.graphemes(true);
+    let mut slots = String::new();
+    for grapheme in text.graphemes(true) {
+        match mask.next()? {
+            place if place == slot => slots.push_str(grapheme),
+            literal if literal == grapheme => (),
+            _ => return None,
+        }
+    }
+    Some(slots)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fit_mask_collects_the_slots() {
+        assert_eq!(fit_mask("12/3", "__/__"), Some(String::from("123")));
+        assert_eq!(fit_mask("", "__/__"), Some(String::new()));
+        assert_eq!(fit_mask("123", "__/__"), None);
+        assert_eq!(fit_mask("12/345", "__/__"), None);
+        // a decomposed grapheme takes a single place
+        assert_eq!(fit_mask("e\u{301}/", "_/"), Some(String::from("e\u{301}")));
+    }
+}
@@ -0,0 +1,65 @@
+use super::css::Css;
+use std::fs;
+use std::path::PathBuf;
+use std::time::{Duration, Instant, SystemTime};
+
+/// minimal time between two checks of the file
+pub const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// watches a stylesheet file by polling its modification time
+pub struct CssWatcher {
+    pub path: PathBuf,
+    modified: Option<SystemTime>,
+    last_poll: Option<Instant>,
+    /// why the file could not be loaded the last time, shown by the `Screen`
+    pub error: Option<String>,
+}
+impl CssWatcher {
+    pub fn new<P: Into<PathBuf>>(path: P) -> Self {
+        Self {
+            path: path.into(),
+            modified: None,
+            last_poll: None,
+            error: None,
+        }
+    }
+
+    /// the stylesheet parsed again when the file changed since the last poll
+    pub fn poll(&mut self) -> Option<Css> {
+        if self
+            .last_poll
+            .is_some_and(|last| last.elapsed() < POLL_INTERVAL)
+        {
+            return None;
+        }
+        self.last_poll = Some(Instant::now());
+
+        let modified = match fs::metadata(&self.path).and_then(|m| m.modified()) {
+            Ok(modified) => modified,
+            Err(error) => {
+                // the file is read again once it is back
+                self.modified = None;
+                self.error = Some(format!("{}: {}", self.path.display(), error));
+                return None;
+            }
+        };
+        if self.modified == Some(modified) {
+            return None;
+        }
+        self.modified = Some(modified);
+
+        let css = fs::read_to_string(&self.path)
+            .map_err(|error| error.to_string())
+            .and_then(|source| Css::parse(&source).map_err(|error| error.to_string()));
+        match css {
+            Ok(css) => {
+                self.error = None;
+                Some(css)
+            }
+            Err(error) => {
+                self.error = Some(format!("{}: {}", self.path.display(), error));
+                None
+            }
+        }
+    }
+}
@@ -1,7 +1,11 @@
-use super::{Widget, TAB_WIDTH};
+use super::Widget;
 use crate::events::{Event, KeyCode, MouseEvent, MouseEventKind};
-use crate::ui::style::{Style, StyleSheet};
-use crate::{Position, Size};
+use crate::ui::border::Border;
+use crate::ui::style::{Color, State, Style, StyleSheet};
+use crate::ui::cell::{styled_canvas, Canvas};
+use crate::ui::theme::Role;
+use crate::ui::text_layout;
+use crate::{Position, Rect, Size};
 
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct Button {
@@ -9,22 +13,39 @@ pub struct Button {
     pub size: Size,
 
     style: StyleSheet,
+    // style from before a stylesheet was applied, stylesheets cascade from it
+    base_style: Option<StyleSheet>,
 
     pub text: String,
+    pub border: Border,
     pub onclick_message: Option<String>,
     pub selected: bool,
+    pub disabled: bool,
+    hovered: bool,
+    pressed: bool,
+
+    pub id: Option<String>,
+    pub classes: Vec<String>,
 }
 impl Button {
     pub fn new<S: Into<String>>(text: S, style: StyleSheet) -> Self {
         Self {
-            position: Position { x: 0.0, y: 0.0 },
-            size: Size { x: 0.0, y: 0.0 },
+            position: Position::default(),
+            size: Size::default(),
 
             style,
+            base_style: None,
 
             text: text.into(),
+            border: Border::plain(),
             onclick_message: None,
             selected: false,
+            disabled: false,
+            hovered: false,
+            pressed: false,
+
+            id: None,
+            classes: Vec::new(),
         }
     }
 
@@ -32,51 +53,67 @@ impl Button {
         self.onclick_message = Some(message.into());
         self
     }
-}
 
-impl Widget for Button {
-    fn to_char_array(&self) -> Vec<Vec<char>> {
-        let mut buffer: Vec<Vec<char>> =
-            vec![vec![' '; self.size.x as usize]; self.size.y as usize];
-
-        // draws border
-        for y in 0..self.size.y as usize {
-            for x in 0..self.size.x as usize {
-                if y == 0
-                    || y == self.size.y as usize - 1
-                    || x == 0
-                    || x == self.size.x as usize - 1
-                {
-                    buffer[y as usize][x as usize] = if self.selected { '#' } else { '+' }
-                }
-            }
+    pub fn with_border(mut self, border: Border) -> Self {
+        self.border = border;
+        self
+    }
+
+    /// border with the kind replaced by the style
+    pub fn effective_border(&self) -> Border {
+        Border {
+            kind: self.style.border.unwrap_or(self.border.kind),
+            ..self.border.clone()
         }
+    }
+
+    pub fn with_id<S: Into<String>>(mut self, id: S) -> Self {
+        self.id = Some(id.into());
+        self
+    }
 
-        let chars: Vec<char> = self.text.chars().collect();
+    pub fn with_class<S: Into<String>>(mut self, class: S) -> Self {
+        self.classes.push(class.into());
+        self
+    }
+}
 
-        let mut x: usize = 1;
-        let mut y: usize = 1;
-        for char in chars {
-            match char {
-                '\n' => {
-                    y += 1;
-                    x = 0;
-                }
-                '\t' => {
-                    x += TAB_WIDTH;
-                }
-                char => {
-                    if (x as f32) < self.size.x - 1.0 && (y as f32) < self.size.y - 1.0 {
-                        buffer[y][x] = char;
-                    }
-                    x += 1;
-                }
-            }
-        }
+impl Widget for Button {
+    fn to_cell_array(&self) -> Canvas {
+        let style = self.style.for_state(self.get_state());
+        let mut buffer: Canvas = styled_canvas(self.size, &style);
+
+        let border = self.effective_border();
+        border.draw(
+            &mut buffer,
+            style.border.unwrap_or(border.kind),
+            style.border_color.unwrap_or(Color::Role(Role::Border)),
+            style.attributes,
+        );
+
+        // text is drawn inside of the border and padding
+        let area = Rect::new(Position::default(), self.size)
+            .inset(border.width())
+            .inset(style.padding);
+        text_layout::draw_text(&mut buffer, area, &self.text, &style);
 
         buffer
     }
 
+    fn measure(&self, width: usize) -> Size {
+        let border = self.effective_border().width();
+        let spacing = self.style.padding.horizontal() + border.horizontal();
+        let text = text_layout::measure(
+            &self.text,
+            width.saturating_sub(spacing),
+            &self.style,
+        );
+        Size {
+            x: text.x + spacing,
+            y: text.y + self.style.padding.vertical() + border.vertical(),
+        }
+    }
+
     fn get_position(&self) -> Position {
         self.position
     }
@@ -94,25 +131,24 @@ impl Widget for Button {
     }
 
     fn handle_input_event(&mut self, event: Event) -> Vec<Vec<String>> {
+        if self.disabled {
+            return Vec::new();
+        }
         match event {
-            Event::Mouse(event) => match event {
-                MouseEvent { kind, .. } => match kind {
-                    MouseEventKind::Down(_) => {
-                        if let Some(message) = self.onclick_message.clone() {
-                            return vec![vec![String::from(message)]];
-                        }
-                    }
-                    _ => (),
-                },
-            },
-            Event::Key(_modifier, code) => match code {
-                KeyCode::Enter => {
-                    if let Some(msg) = self.onclick_message.clone() {
-                        return vec![vec![String::from(msg)]];
-                    }
+            Event::Mouse(MouseEvent {
+                kind: MouseEventKind::Up(_),
+                ..
+            }) => self.pressed = false,
+            Event::Mouse(MouseEvent {
+                kind: MouseEventKind::Down(_),
+                ..
+            })
+            | Event::Key(_, KeyCode::Enter) => {
+                self.pressed = matches!(event, Event::Mouse(_));
+                if let Some(message) = self.onclick_message.clone() {
+                    return vec![vec![message]];
                 }
-                _ => (),
-            },
+            }
             _ => (),
         }
         Vec::new()
@@ -125,6 +161,37 @@ impl Widget for Button {
     fn select(&mut self, selected: bool) {
         self.selected = selected
     }
+
+    fn get_state(&self) -> State {
+        State {
+            focused: self.selected,
+            hovered: self.hovered,
+            pressed: self.pressed,
+            invalid: false,
+            disabled: self.disabled,
+        }
+    }
+
+    fn set_hovered(&mut self, hovered: bool) {
+        self.hovered = hovered;
+        // the mouse button may be released somewhere else
+        if !hovered {
+            self.pressed = false;
+        }
+    }
+
+    fn set_disabled(&mut self, disabled: bool) {
+        self.disabled = disabled;
+        self.pressed = false;
+    }
+
+    fn get_id(&self) -> Option<String> {
+        self.id.clone()
+    }
+
+    fn get_classes(&self) -> Vec<String> {
+        self.classes.clone()
+    }
 }
 
 impl Style for Button {
@@ -134,6 +201,15 @@ impl Style for Button {
 
     fn set_style(&mut self, style: StyleSheet) {
         self.style = style;
+        self.base_style = None;
+    }
+
+    fn get_base_style(&self) -> Option<StyleSheet> {
+        self.base_style
+    }
+
+    fn set_base_style(&mut self, style: Option<StyleSheet>) {
+        self.base_style = style;
     }
 
     fn apply_style(&mut self) {
@@ -1,51 +1,186 @@
-use super::{Widget, Widgets};
-use crate::events::{Event, KeyCode, MouseEvent};
-use crate::ui::style::{Style, StyleSheet, Length};
-use crate::{Position, Size};
+use super::{Cursor, Popup, Widget, Widgets};
+use crate::events::{Event, KeyCode, KeyMod, MouseButton, MouseEvent, MouseEventKind};
+use crate::ui::border::Border;
+use crate::ui::cell::{styled_canvas, Canvas};
+use crate::ui::style::{Anchor, Color, Length, Padding, Spacing, State, Style, StyleSheet};
+use crate::ui::theme::Role;
+use crate::{Position, Rect, Size};
 
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub enum ContainerKind {
     Row,
     Column,
+    /// children are placed on top of each other using their `placement` and `z_index`
+    Stack,
+    /// children are placed from left to right and wrap onto the next line when the width runs out
+    Flow,
 }
 
+/// layout overrides that are used while the size of a container is inside of the given bounds
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Breakpoint {
+    pub min_width: Option<usize>,
+    pub max_width: Option<usize>,
+    pub min_height: Option<usize>,
+    pub max_height: Option<usize>,
+
+    pub kind: Option<ContainerKind>,
+    pub padding: Option<Padding>,
+    /// indexes of the content that is not shown
+    pub hidden: Vec<usize>,
+}
+impl Breakpoint {
+    pub fn max_width(width: usize) -> Self {
+        Self {
+            max_width: Some(width),
+            ..Default::default()
+        }
+    }
+
+    pub fn min_width(width: usize) -> Self {
+        Self {
+            min_width: Some(width),
+            ..Default::default()
+        }
+    }
+
+    pub fn max_height(height: usize) -> Self {
+        Self {
+            max_height: Some(height),
+            ..Default::default()
+        }
+    }
+
+    pub fn min_height(height: usize) -> Self {
+        Self {
+            min_height: Some(height),
+            ..Default::default()
+        }
+    }
+
+    pub fn with_kind(mut self, kind: ContainerKind) -> Self {
+        self.kind = Some(kind);
+        self
+    }
+
+    pub fn with_padding(mut self, padding: Padding) -> Self {
+        self.padding = Some(padding);
+        self
+    }
+
+    pub fn hide(mut self, index: usize) -> Self {
+        self.hidden.push(index);
+        self
+    }
+
+    pub fn matches(&self, size: Size) -> bool {
+        self.min_width.is_none_or(|min| size.x >= min)
+            && self.max_width.is_none_or(|max| size.x <= max)
+            && self.min_height.is_none_or(|min| size.y >= min)
+            && self.max_height.is_none_or(|max| size.y <= max)
+    }
+}
+
+/// lines scrolled by a single turn of the mouse wheel
+pub const SCROLL_STEP: usize = 3;
+
+/// state of a scrollable container, its content is laid out in an unbounded area of which only
+/// the viewport starting at `offset` is shown
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Scroll {
+    pub offset: Position,
+    /// size of the area occupied by the content
+    pub content_size: Size,
+    pub vertical_bar: bool,
+    pub horizontal_bar: bool,
+}
+
+/// state of a container split into resizable panes, which are separated by dividers
+#[derive(Clone, Debug, PartialEq)]
+pub struct Split {
+    /// share of the space for every pane
+    pub ratios: Vec<f32>,
+    /// minimum size of every pane in cells
+    pub min_size: usize,
+    /// index of the divider that is dragged by the mouse
+    pub dragging: Option<usize>,
+}
+impl Eq for Split {}
+
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct Container {
     pub position: Position,
     pub size: Size,
 
     pub style: StyleSheet,
+    // style from before a stylesheet was applied, stylesheets cascade from it
+    base_style: Option<StyleSheet>,
     pub kind: ContainerKind,
 
     pub content: Vec<Widgets>,
-    pub borders: [char; 12],
+    pub border: Border,
 
     pub selected: bool,
     pub selection_index: usize,
     pub tab_selector: bool,
     pub always_selected: bool,
+    pub disabled: bool,
+    hovered: bool,
+
+    pub id: Option<String>,
+    pub classes: Vec<String>,
+
+    pub breakpoints: Vec<Breakpoint>,
+    /// index of the breakpoint used by the last layout
+    pub active_breakpoint: Option<usize>,
+
+    pub scroll: Option<Scroll>,
+    pub split: Option<Split>,
 }
 impl Container {
     pub fn new(kind: ContainerKind, content: Vec<Widgets>, style: StyleSheet) -> Self {
         Self {
-            position: Position { x: 0.0, y: 0.0 },
-            size: Size { x: 0.0, y: 0.0 },
+            position: Position::default(),
+            size: Size::default(),
 
             style,
+            base_style: None,
             kind,
 
             content,
-            borders: [' '; 12],
+            border: Border::none(),
 
             selected: false,
             selection_index: 0,
             tab_selector: false,
             always_selected: false,
+            disabled: false,
+            hovered: false,
+
+            id: None,
+            classes: Vec::new(),
+
+            breakpoints: Vec::new(),
+            active_breakpoint: None,
+
+            scroll: None,
+            split: None,
         }
     }
 
     pub fn push(&mut self, widget: Widgets) {
-        self.content.push(widget)
+        self.content.push(widget);
+        self.fit_ratios();
+    }
+
+    pub fn with_id<S: Into<String>>(mut self, id: S) -> Self {
+        self.id = Some(id.into());
+        self
+    }
+
+    pub fn with_class<S: Into<String>>(mut self, class: S) -> Self {
+        self.classes.push(class.into());
+        self
     }
 
     pub fn with_tab_selector(mut self) -> Self {
@@ -59,13 +194,65 @@ impl Container {
     }
 
     pub fn with_default_borders(mut self) -> Self {
-        let borders = ['┌', '┐', '┘', '└', '─', '│', '.', '.', '.', '.', '.', ':'];
-        self.borders = borders;
+        self.border = Border::plain();
+        self
+    }
+
+    pub fn with_border(mut self, border: Border) -> Self {
+        self.border = border;
+        self
+    }
+
+    /// border with the kind replaced by the style
+    pub fn effective_border(&self) -> Border {
+        Border {
+            kind: self.style.border.unwrap_or(self.border.kind),
+            ..self.border.clone()
+        }
+    }
+
+    /// makes the content scrollable instead of clipping it, optionally with scrollbars
+    pub fn scrollable(mut self, vertical_bar: bool, horizontal_bar: bool) -> Self {
+        self.scroll = Some(Scroll {
+            vertical_bar,
+            horizontal_bar,
+            ..Default::default()
+        });
         self
     }
 
-    pub fn with_borders(mut self, borders: [char; 12]) -> Self {
-        self.borders = borders;
+    /// splits the content into panes of equal size along the `Row` or `Column`,
+    /// which can be resized by dragging the dividers or using `Alt` and the arrow keys
+    pub fn split(mut self, min_size: usize) -> Self {
+        self.split = Some(Split {
+            ratios: Vec::new(),
+            min_size,
+            dragging: None,
+        });
+        self.fit_ratios();
+        self
+    }
+
+    /// share of every pane by the index of its widget, missing ones get the average share
+    pub fn with_ratios(mut self, ratios: Vec<f32>) -> Self {
+        if let Some(split) = self.split.as_mut() {
+            split.ratios = ratios;
+        }
+        self.fit_ratios();
+        self
+    }
+
+    // a ratio for every widget, the content may have changed since the ratios were set
+    fn fit_ratios(&mut self) {
+        let count = self.content.len();
+        if let Some(split) = self.split.as_mut() {
+            fit_ratios(&mut split.ratios, count);
+        }
+    }
+
+    /// breakpoints are checked in the order they were added, the first one that matches is used
+    pub fn with_breakpoint(mut self, breakpoint: Breakpoint) -> Self {
+        self.breakpoints.push(breakpoint);
         self
     }
 
@@ -78,72 +265,651 @@ impl Container {
     }
 }
 
-impl Widget for Container {
-    fn to_char_array(&self) -> Vec<Vec<char>> {
-        if self.size.x >= 1.0 && self.size.y >= 1.0 {
-            let mut array: Vec<Vec<char>> =
-                vec![vec![' '; self.size.x as usize]; self.size.y as usize];
-            for c in &self.content {
-                let arr = c.to_char_array();
-                let pos = c.get_position();
-                let size = c.get_size();
-                for y in 0..size.y as usize {
-                    for x in 0..size.x as usize {
-                        let array_x = x + pos.x as usize;
-                        let array_y = y + pos.y as usize;
-
-                        if self.size.x.floor() > array_x as f32
-                            && self.size.y.floor() > array_y as f32
-                        {
-                            array[array_y][array_x] = arr[y][x]
-                        }
-                    }
+impl Container {
+    /// indexes of the content sorted from bottom to top, widgets with equal `z_index` keep their order
+    pub fn draw_order(&self) -> Vec<usize> {
+        let mut order: Vec<usize> = (0..self.content.len()).collect();
+        order.retain(|i| !self.is_hidden(*i));
+        order.sort_by_key(|i| self.content[*i].get_style().z_index);
+        order
+    }
+
+    fn breakpoint(&self) -> Option<&Breakpoint> {
+        self.active_breakpoint.map(|i| &self.breakpoints[i])
+    }
+
+    /// kind used for the layout, which may be changed by the active breakpoint
+    pub fn layout_kind(&self) -> ContainerKind {
+        self.breakpoint()
+            .and_then(|b| b.kind.clone())
+            .unwrap_or_else(|| self.kind.clone())
+    }
+
+    pub fn padding(&self) -> Padding {
+        self.breakpoint()
+            .and_then(|b| b.padding)
+            .unwrap_or(self.style.padding)
+    }
+
+    /// indexes of the content hidden by the active breakpoint
+    pub fn hidden(&self) -> Vec<usize> {
+        self.breakpoint()
+            .map(|b| b.hidden.clone())
+            .unwrap_or_default()
+    }
+
+    pub fn is_hidden(&self, index: usize) -> bool {
+        self.breakpoint().is_some_and(|b| b.hidden.contains(&index))
+    }
+
+    /// width of each side of the border, zero when no borders are drawn
+    pub fn border_width(&self) -> Spacing {
+        self.effective_border().width()
+    }
+
+    /// area inside of the border, padding and scrollbars, in which the content is laid out
+    pub fn content_box(&self) -> Rect {
+        self.content_box_in(self.size, self.padding())
+    }
+
+    fn content_box_in(&self, size: Size, padding: Padding) -> Rect {
+        let border = self.border_width();
+        let (bar_x, bar_y) = self.scrollbar_width();
+        Rect {
+            x: border.x_start + padding.x_start,
+            y: border.y_start + padding.y_start,
+            width: size
+                .x
+                .saturating_sub(border.horizontal() + padding.horizontal() + bar_x),
+            height: size
+                .y
+                .saturating_sub(border.vertical() + padding.vertical() + bar_y),
+        }
+    }
+
+    // columns of the vertical and rows of the horizontal scrollbar
+    fn scrollbar_width(&self) -> (usize, usize) {
+        match &self.scroll {
+            Some(scroll) => (scroll.vertical_bar as usize, scroll.horizontal_bar as usize),
+            None => (0, 0),
+        }
+    }
+
+    /// offset of the viewport into the content, zero when not scrollable
+    pub fn scroll_offset(&self) -> Position {
+        self.scroll
+            .as_ref()
+            .map(|scroll| scroll.offset)
+            .unwrap_or_default()
+    }
+
+    /// scrolls by the given amount of cells, clamped to the size of the content
+    pub fn scroll_by(&mut self, x: isize, y: isize) {
+        let inner = self.content_box();
+        if let Some(scroll) = self.scroll.as_mut() {
+            let max_x = scroll.content_size.x.saturating_sub(inner.width);
+            let max_y = scroll.content_size.y.saturating_sub(inner.height);
+            scroll.offset.x = scroll.offset.x.saturating_add_signed(x).min(max_x);
+            scroll.offset.y = scroll.offset.y.saturating_add_signed(y).min(max_y);
+        }
+    }
+
+    /// rect of the focused widget, nested containers give the rect of their focused widget.
+    /// relative to the content of this container
+    pub fn focused_rect(&self) -> Option<Rect> {
+        let hidden = self.hidden();
+        let index = self
+            .draw_order()
+            .into_iter()
+            .rev()
+            .find(|i| self.content[*i].is_selected() && !hidden.contains(i))?;
+        let widget = &self.content[index];
+        let rect = widget.get_rect();
+        match widget {
+            Widgets::Container(c) => match c.focused_rect() {
+                Some(nested) => {
+                    let offset = c.scroll_offset();
+                    Some(Rect {
+                        x: (rect.x + nested.x).saturating_sub(offset.x),
+                        y: (rect.y + nested.y).saturating_sub(offset.y),
+                        ..nested
+                    })
                 }
+                None => Some(rect),
+            },
+            _ => Some(rect),
+        }
+    }
+
+    /// scrolls the least amount needed for `rect` to be inside of the viewport
+    pub fn scroll_to(&mut self, rect: Rect) {
+        let inner = self.content_box();
+        let offset = self.scroll_offset();
+
+        // rect and viewport relative to the start of the content
+        let x = rect.x.saturating_sub(inner.x);
+        let y = rect.y.saturating_sub(inner.y);
+
+        let mut target = offset;
+        if x + rect.width > offset.x + inner.width {
+            target.x = (x + rect.width).saturating_sub(inner.width);
+        }
+        if x < target.x {
+            target.x = x;
+        }
+        if y + rect.height > offset.y + inner.height {
+            target.y = (y + rect.height).saturating_sub(inner.height);
+        }
+        if y < target.y {
+            target.y = y;
+        }
+
+        self.scroll_by(
+            target.x as isize - offset.x as isize,
+            target.y as isize - offset.y as isize,
+        );
+    }
+
+    // nested scrollable containers get to handle scrolling first
+    fn is_scrollable(widget: &Widgets) -> bool {
+        match widget {
+            Widgets::Container(c) => c.scroll.is_some(),
+            Widgets::TextArea(_) => true,
+            _ => false,
+        }
+    }
+
+    // size of the laid out content including the margins, relative to the content box
+    fn content_extent(&self, rects: &[Option<Rect>], inner: Rect) -> Size {
+        let mut content_size = Size::default();
+        for (c, rect) in self.content.iter().zip(rects) {
+            let Some(rect) = rect else {
+                continue;
+            };
+            let margin = c.get_style().margin;
+            let right = (rect.right() + margin.x_end).saturating_sub(inner.x);
+            let bottom = (rect.bottom() + margin.y_end).saturating_sub(inner.y);
+            content_size.x = content_size.x.max(right);
+            content_size.y = content_size.y.max(bottom);
+        }
+        content_size
+    }
+
+    // updates the size of the scrollable area after layout
+    fn update_content_size(&mut self, content_size: Size) {
+        if let Some(scroll) = self.scroll.as_mut() {
+            scroll.content_size = content_size;
+        }
+        // keeps the offset inside of the new content size
+        self.scroll_by(0, 0);
+    }
+
+    fn draw_scrollbars(&self, array: &mut Canvas) {
+        let inner = self.content_box();
+        let scroll = match &self.scroll {
+            Some(scroll) => scroll,
+            None => return,
+        };
+
+        // scrollbars are only drawn when there is room for them
+        if scroll.vertical_bar && inner.right() < self.size.x {
+            let (start, length) =
+                scrollbar_thumb(inner.height, scroll.content_size.y, scroll.offset.y);
+            for y in 0..inner.height {
+                let thumb = y >= start && y < start + length;
+                let cell = &mut array[inner.y + y][inner.right()];
+                cell.symbol = String::from(if thumb { "█" } else { "░" });
+                cell.fg = Color::Role(if thumb { Role::Accent } else { Role::Border });
             }
+        }
+        if scroll.horizontal_bar && inner.bottom() < self.size.y {
+            let (start, length) =
+                scrollbar_thumb(inner.width, scroll.content_size.x, scroll.offset.x);
+            for x in 0..inner.width {
+                let thumb = x >= start && x < start + length;
+                let cell = &mut array[inner.bottom()][inner.x + x];
+                cell.symbol = String::from(if thumb { "█" } else { "░" });
+                cell.fg = Color::Role(if thumb { Role::Accent } else { Role::Border });
+            }
+        }
+    }
 
-            // draws border
-            if self.borders != [' '; 12] {
-                let x_edge = array[0].len() - 1;
-                let y_edge = array.len() - 1;
-    
-                if self.selected || self.always_selected {
-                    // upper and bottom line
-                    for x in 0..x_edge {
-                        array[0][x] = self.borders[4];
-                        array[y_edge][x] = self.borders[4]
-                    }
-    
-                    // right and left line
-                    for y in 0..y_edge {
-                        array[y][x_edge] = self.borders[5];
-                        array[y][0] = self.borders[5];
+    // indexes of the shown panes of a split container
+    fn panes(&self) -> Vec<usize> {
+        let hidden = self.hidden();
+        (0..self.content.len())
+            .filter(|i| !hidden.contains(i))
+            .collect()
+    }
+
+    // panes fill the content box along the main axis, separated by dividers of one cell
+    fn split_rects(
+        &self,
+        split: &Split,
+        kind: &ContainerKind,
+        inner: Rect,
+        hidden: &[usize],
+    ) -> Vec<Option<Rect>> {
+        let panes: Vec<usize> = (0..self.content.len())
+            .filter(|i| !hidden.contains(i))
+            .collect();
+        let mut ratios = split.ratios.clone();
+        fit_ratios(&mut ratios, self.content.len());
+
+        // dividers are not part of the panes
+        let length = match kind {
+            ContainerKind::Column => inner.height,
+            _ => inner.width,
+        };
+        let available = length.saturating_sub(panes.len().saturating_sub(1));
+        let pane_ratios: Vec<f32> = panes.iter().map(|i| ratios[*i]).collect();
+        let sizes = split_sizes(available, &pane_ratios, split.min_size);
+
+        let mut rects = vec![None; self.content.len()];
+        let mut start = 0;
+        for (pane, size) in panes.iter().zip(sizes) {
+            rects[*pane] = Some(match kind {
+                ContainerKind::Column => Rect {
+                    x: inner.x,
+                    y: inner.y + start,
+                    width: inner.width,
+                    height: size,
+                },
+                _ => Rect {
+                    x: inner.x + start,
+                    y: inner.y,
+                    width: size,
+                    height: inner.height,
+                },
+            });
+            start += size + 1;
+        }
+        rects
+    }
+
+    // position of the divider behind the pane, on the main axis of the container
+    fn divider_position(&self, pane: usize) -> usize {
+        let rect = self.content[pane].get_rect();
+        match self.layout_kind() {
+            ContainerKind::Column => rect.bottom(),
+            _ => rect.right(),
+        }
+    }
+
+    // index of the divider at the given coordinates
+    fn divider_at(&self, x: usize, y: usize) -> Option<usize> {
+        self.split.as_ref()?;
+        let inner = self.content_box();
+        if !inner.contains(x, y) {
+            return None;
+        }
+        let position = match self.layout_kind() {
+            ContainerKind::Column => y,
+            _ => x,
+        };
+        let panes = self.panes();
+        (0..panes.len().saturating_sub(1)).find(|d| self.divider_position(panes[*d]) == position)
+    }
+
+    /// moves a divider to the given position on the main axis, keeping the minimum size of both panes
+    pub fn move_divider(&mut self, divider: usize, position: usize) {
+        let panes = self.panes();
+        if divider + 1 >= panes.len() {
+            return;
+        }
+        let (first, second) = (panes[divider], panes[divider + 1]);
+        self.fit_ratios();
+        let kind = self.layout_kind();
+        let first_rect = self.content[first].get_rect();
+        let second_rect = self.content[second].get_rect();
+        let (start, end) = match kind {
+            ContainerKind::Column => (first_rect.y, second_rect.bottom()),
+            _ => (first_rect.x, second_rect.right()),
+        };
+
+        let split = match self.split.as_mut() {
+            Some(split) => split,
+            None => return,
+        };
+        let combined = end.saturating_sub(start + 1);
+        if combined == 0 {
+            return;
+        }
+        let max = combined.saturating_sub(split.min_size);
+        let size = position
+            .saturating_sub(start)
+            .min(max)
+            .max(split.min_size.min(max));
+
+        // only the two panes next to the divider change their share
+        let share = split.ratios[first] + split.ratios[second];
+        split.ratios[first] = share * size as f32 / combined as f32;
+        split.ratios[second] = share - split.ratios[first];
+
+        self.apply_style();
+    }
+
+    fn draw_dividers(&self, array: &mut Canvas) {
+        let split = match &self.split {
+            Some(split) => split,
+            None => return,
+        };
+        let kind = self.layout_kind();
+        if kind == ContainerKind::Stack {
+            return;
+        }
+        let inner = self.content_box();
+        let panes = self.panes();
+
+        // the last pane has no divider behind it
+        let dividers = panes.len().saturating_sub(1);
+        for (divider, pane) in panes[..dividers].iter().enumerate() {
+            let position = self.divider_position(*pane);
+            let dragged = split.dragging == Some(divider);
+            let color = Color::Role(if dragged { Role::Accent } else { Role::Border });
+            match kind {
+                ContainerKind::Column => {
+                    if let Some(row) = array.get_mut(position) {
+                        for cell in row.iter_mut().skip(inner.x).take(inner.width) {
+                            cell.symbol = String::from(if dragged { "━" } else { "─" });
+                            cell.fg = color;
+                        }
                     }
-    
-                    array[0][0] = self.borders[0]; // upper left border
-                    array[0][x_edge] = self.borders[1]; // upper right border
-                    array[y_edge][x_edge] = self.borders[2]; // bottom right
-                    array[y_edge][0] = self.borders[3]; // bottom left
-                } else {
-                    let offset: usize = 6;
-                    // upper and bottom line
-                    for x in 0..x_edge {
-                        array[0][x] = self.borders[4 + offset];
-                        array[y_edge][x] = self.borders[4 + offset]
+                }
+                _ => {
+                    for row in array.iter_mut().skip(inner.y).take(inner.height) {
+                        if let Some(cell) = row.get_mut(position) {
+                            cell.symbol = String::from(if dragged { "┃" } else { "│" });
+                            cell.fg = color;
+                        }
                     }
-    
-                    // right and left line
-                    for y in 0..y_edge {
-                        array[y][x_edge] = self.borders[5 + offset];
-                        array[y][0] = self.borders[5 + offset];
+                }
+            }
+        }
+    }
+
+    // widgets are placed in lines, relative lengths refer to the whole content box
+    fn flow_rects(&self, inner: Rect, hidden: &[usize]) -> Vec<Option<Rect>> {
+        let mut rects = Vec::with_capacity(self.content.len());
+        let mut x: usize = 0;
+        let mut y: usize = 0;
+        let mut line_height: usize = 0;
+        for (i, c) in self.content.iter().enumerate() {
+            if hidden.contains(&i) {
+                rects.push(None);
+                continue;
+            }
+
+            let style = c.get_style();
+            let margin = style.margin;
+
+            let available_x = inner.width.saturating_sub(margin.horizontal()) as f32;
+            let available_y = inner.height.saturating_sub(margin.vertical()) as f32;
+            let (width, height) = resolve_lengths(c, &style, available_x, available_y);
+            let (_, width) = round_span(0.0, width);
+            let (_, height) = round_span(0.0, height);
+
+            // wraps onto the next line, unless the widget is the first one of the line
+            let outer_width = margin.horizontal() + width;
+            if x > 0 && x + outer_width > inner.width {
+                x = 0;
+                y += line_height;
+                line_height = 0;
+            }
+
+            rects.push(Some(Rect {
+                x: inner.x + x + margin.x_start,
+                y: inner.y + y + margin.y_start,
+                width,
+                height,
+            }));
+
+            x += outer_width;
+            line_height = line_height.max(margin.vertical() + height);
+        }
+        rects
+    }
+
+    // every widget is positioned independently relative to the edges of the content box
+    fn stack_rects(&self, inner: Rect, hidden: &[usize]) -> Vec<Option<Rect>> {
+        let mut rects = Vec::with_capacity(self.content.len());
+        for (i, c) in self.content.iter().enumerate() {
+            if hidden.contains(&i) {
+                rects.push(None);
+                continue;
+            }
+
+            let style = c.get_style();
+            let margin = style.margin;
+
+            let available_x = inner.width.saturating_sub(margin.horizontal()) as f32;
+            let available_y = inner.height.saturating_sub(margin.vertical()) as f32;
+            let (width, height) = resolve_lengths(c, &style, available_x, available_y);
+            let (_, width) = round_span(0.0, width);
+            let (_, height) = round_span(0.0, height);
+
+            let x: usize = match style.placement.x {
+                Anchor::Start(offset) => margin.x_start + offset,
+                Anchor::End(offset) => inner.width.saturating_sub(margin.x_end + width + offset),
+                Anchor::Center => {
+                    margin.x_start + inner.width.saturating_sub(margin.horizontal() + width) / 2
+                }
+            };
+            let y: usize = match style.placement.y {
+                Anchor::Start(offset) => margin.y_start + offset,
+                Anchor::End(offset) => inner.height.saturating_sub(margin.y_end + height + offset),
+                Anchor::Center => {
+                    margin.y_start + inner.height.saturating_sub(margin.vertical() + height) / 2
+                }
+            };
+
+            rects.push(Some(Rect {
+                x: inner.x + x,
+                y: inner.y + y,
+                width,
+                height,
+            }));
+        }
+        rects
+    }
+
+    // widgets follow each other along the main axis, relative lengths refer to the space left in
+    // the content box. scrollable content is not limited by the viewport, so relative and auto
+    // lengths refer to the whole viewport there
+    fn linear_rects(
+        &self,
+        kind: &ContainerKind,
+        inner: Rect,
+        hidden: &[usize],
+    ) -> Vec<Option<Rect>> {
+        let unbounded = self.scroll.is_some();
+        let mut rects = Vec::with_capacity(self.content.len());
+        // the cursor is kept fractional and only the edges of each widget are rounded to cells
+        let mut x_pos: f32 = 0.0;
+        let mut y_pos: f32 = 0.0;
+        for (i, c) in self.content.iter().enumerate() {
+            if hidden.contains(&i) {
+                rects.push(None);
+                continue;
+            }
+
+            let style = c.get_style();
+            let margin = style.margin;
+
+            let (used_x, used_y) = if unbounded {
+                (0.0, 0.0)
+            } else {
+                (x_pos, y_pos)
+            };
+            let available_x = inner.width as f32 - used_x - margin.horizontal() as f32;
+            let available_y = inner.height as f32 - used_y - margin.vertical() as f32;
+            let (width, height) =
+                resolve_lengths(c, &style, available_x.max(0.0), available_y.max(0.0));
+
+            let (x, cell_width) = round_span(x_pos + margin.x_start as f32, width);
+            let (y, cell_height) = round_span(y_pos + margin.y_start as f32, height);
+            rects.push(Some(Rect {
+                x: inner.x + x,
+                y: inner.y + y,
+                width: cell_width,
+                height: cell_height,
+            }));
+
+            /* creates room for next widget */
+            match kind {
+                ContainerKind::Row => {
+                    y_pos = 0.0;
+                    x_pos += (margin.x_start + margin.x_end) as f32 + width;
+                }
+                ContainerKind::Column => {
+                    x_pos = 0.0;
+                    y_pos += (margin.y_start + margin.y_end) as f32 + height;
+                }
+                ContainerKind::Stack | ContainerKind::Flow => (),
+            }
+        }
+        rects
+    }
+
+    // rects of the widgets laid out in `inner`, none for hidden widgets
+    fn layout_rects(
+        &self,
+        kind: &ContainerKind,
+        inner: Rect,
+        hidden: &[usize],
+    ) -> Vec<Option<Rect>> {
+        match (kind, &self.split) {
+            (ContainerKind::Stack, _) => self.stack_rects(inner, hidden),
+            (_, Some(split)) => self.split_rects(split, kind, inner, hidden),
+            (ContainerKind::Flow, None) => self.flow_rects(inner, hidden),
+            (_, None) => self.linear_rects(kind, inner, hidden),
+        }
+    }
+}
+
+// gives every widget a ratio, missing ones get the average share
+fn fit_ratios(ratios: &mut Vec<f32>, count: usize) {
+    let share = match ratios.len() {
+        0 => 1.0 / count.max(1) as f32,
+        length => ratios.iter().sum::<f32>() / length as f32,
+    };
+    ratios.resize(count, share);
+}
+
+// sizes of panes sharing `available` cells by their ratios, every pane gets at least `min_size`
+// cells if possible
+fn split_sizes(available: usize, ratios: &[f32], min_size: usize) -> Vec<usize> {
+    let total: f32 = ratios.iter().sum();
+    let mut sizes: Vec<usize> = Vec::with_capacity(ratios.len());
+    let mut position: f32 = 0.0;
+    for ratio in ratios {
+        let share = if total > 0.0 {
+            ratio / total
+        } else {
+            1.0 / ratios.len() as f32
+        };
+        let length = share * available as f32;
+        let (_, size) = round_span(position, length);
+        sizes.push(size);
+        position += length;
+    }
+
+    // takes cells from the largest panes for panes below the minimum
+    let min_size = min_size.min(available / ratios.len().max(1));
+    for i in 0..sizes.len() {
+        while sizes[i] < min_size {
+            let largest = (0..sizes.len()).max_by_key(|j| sizes[*j]).unwrap_or(i);
+            if sizes[largest] <= min_size {
+                break;
+            }
+            sizes[largest] -= 1;
+            sizes[i] += 1;
+        }
+    }
+    sizes
+}
+
+// start and length of the thumb of a scrollbar with the given track length
+fn scrollbar_thumb(track: usize, content: usize, offset: usize) -> (usize, usize) {
+    if content <= track || track == 0 {
+        return (0, track);
+    }
+    let length = (track * track / content).clamp(1, track);
+    let max_offset = content - track;
+    let start = (offset * (track - length) + max_offset / 2) / max_offset;
+    (start, length)
+}
+
+// resolves the lengths of a widget, `Length::Auto` is measured from its content
+fn resolve_lengths(
+    widget: &Widgets,
+    style: &StyleSheet,
+    available_x: f32,
+    available_y: f32,
+) -> (f32, f32) {
+    let width = match style.width {
+        Length::Auto => widget.measure(available_x as usize).x as f32,
+        length => length.resolve(available_x),
+    };
+    let height = match style.height {
+        Length::Auto => widget.measure(width.round().max(0.0) as usize).y as f32,
+        length => length.resolve(available_y),
+    };
+    (width, height)
+}
+
+// rounds both edges of a fractional span to whole cells,
+// so adjacent spans share their edges and the remainders get distributed between them
+fn round_span(start: f32, length: f32) -> (usize, usize) {
+    let start_cell = start.max(0.0).round() as usize;
+    let end_cell = (start + length).max(0.0).round() as usize;
+    (start_cell, end_cell.saturating_sub(start_cell))
+}
+
+impl Widget for Container {
+    fn to_cell_array(&self) -> Canvas {
+        if self.size.x >= 1 && self.size.y >= 1 {
+            let style = self.style.for_state(self.get_state());
+            let mut array: Canvas = styled_canvas(self.size, &style);
+
+            // content is clipped to the content box so it never overlaps the border
+            let inner = self.content_box();
+            let offset = self.scroll_offset();
+
+            for i in self.draw_order() {
+                let c = &self.content[i];
+                let arr = c.to_cell_array();
+                let pos = c.get_position();
+                for (y, row) in arr.iter().enumerate() {
+                    for (x, cell) in row.iter().enumerate() {
+                        // content left or above of the viewport is skipped
+                        let (array_x, array_y) = match (
+                            (x + pos.x).checked_sub(offset.x),
+                            (y + pos.y).checked_sub(offset.y),
+                        ) {
+                            (Some(x), Some(y)) => (x, y),
+                            _ => continue,
+                        };
+
+                        if inner.contains(array_x, array_y) {
+                            array[array_y][array_x] = cell.clone()
+                        }
                     }
-    
-                    array[0][0] = self.borders[0 + offset]; // upper left border
-                    array[0][x_edge] = self.borders[1 + offset]; // upper right border
-                    array[y_edge][x_edge] = self.borders[2 + offset]; // bottom right
-                    array[y_edge][0] = self.borders[3 + offset]; // bottom left
                 }
             }
 
+            self.draw_dividers(&mut array);
+            self.draw_scrollbars(&mut array);
+
+            // draws border
+            let border = self.effective_border();
+            border.draw(
+                &mut array,
+                style.border.unwrap_or(border.kind),
+                style.border_color.unwrap_or(Color::Role(Role::Border)),
+                style.attributes,
+            );
+
             array
         } else {
             Vec::new()
@@ -151,67 +917,193 @@ impl Widget for Container {
     }
     fn handle_input_event(&mut self, event: Event) -> Vec<Vec<String>> {
         let mut messages: Vec<Vec<String>> = Vec::new();
+        if self.disabled {
+            return messages;
+        }
 
         match event {
             Event::Resize(_) => (),
             Event::Key(modifier, key) => {
+                let focused = self.focused_rect();
+
                 // sends key event to every selected widget
                 let mut other_container_tab_selecting: bool = false;
-                for widget in self.content.iter_mut() {
-                    if widget.is_selected() {
+                let mut other_container_scrolling: bool = false;
+                let hidden = self.hidden();
+                for (i, widget) in self.content.iter_mut().enumerate() {
+                    if widget.is_selected() && !hidden.contains(&i) {
                         let mut msg =
                             widget.handle_input_event(Event::Key(modifier.clone(), key.clone()));
                         messages.append(&mut msg);
 
-                        match widget {
-                            Widgets::Container(c) => {
-                                if c.tab_selector && c.is_selected() {
-                                    other_container_tab_selecting = true
-                                }
+                        if Self::is_scrollable(widget) {
+                            other_container_scrolling = true
+                        }
+
+                        if let Widgets::Container(c) = widget {
+                            if c.tab_selector && c.is_selected() {
+                                other_container_tab_selecting = true
                             }
-                            _ => (),
                         }
                     }
                 }
                 // tab selector
                 if key == KeyCode::Tab && self.tab_selector && !other_container_tab_selecting {
-                    self.selection_index += 1;
-                    if self.selection_index >= self.content.len() {
-                        self.selection_index = 0;
-                        self.select(false);
+                    // hidden widgets are skipped
+                    for _ in 0..self.content.len() {
+                        self.selection_index += 1;
+                        if self.selection_index >= self.content.len() {
+                            self.selection_index = 0;
+                            self.select(false);
+                        }
+                        let index = self.selection_index;
+                        if !self.is_hidden(index) && !self.content[index].get_state().disabled {
+                            break;
+                        }
                     }
                     // deselection
                     for c in self.content.iter_mut() {
                         c.select(false);
                     }
-                    // selection
-                    self.content[self.selection_index].select(true);
+                    // selection, unless every widget is hidden or disabled
+                    let index = self.selection_index;
+                    if !self.is_hidden(index) && !self.content[index].get_state().disabled {
+                        self.content[index].select(true);
+                    }
+                }
+
+                // keyboard follows focus, also into nested containers
+                if self.scroll.is_some() {
+                    match self.focused_rect() {
+                        Some(rect) if Some(rect) != focused => self.scroll_to(rect),
+                        _ => (),
+                    }
+                }
+
+                // moves the divider next to the selected pane
+                if modifier == KeyMod::Alt && self.split.is_some() {
+                    let step: isize = match (self.layout_kind(), &key) {
+                        (ContainerKind::Row, KeyCode::Left) => -1,
+                        (ContainerKind::Row, KeyCode::Right) => 1,
+                        (ContainerKind::Column, KeyCode::Up) => -1,
+                        (ContainerKind::Column, KeyCode::Down) => 1,
+                        _ => 0,
+                    };
+                    let panes = self.panes();
+                    let selected = panes.iter().position(|i| self.content[*i].is_selected());
+                    if let (true, Some(selected)) = (step != 0 && panes.len() > 1, selected) {
+                        let divider = selected.min(panes.len() - 2);
+                        let position = self
+                            .divider_position(panes[divider])
+                            .saturating_add_signed(step);
+                        self.move_divider(divider, position);
+                    }
+                }
+
+                // scrolls by one page
+                if self.scroll.is_some() && !other_container_scrolling {
+                    let page = self.content_box().height.max(1) as isize;
+                    match key {
+                        KeyCode::PageUp => self.scroll_by(0, -page),
+                        KeyCode::PageDown => self.scroll_by(0, page),
+                        _ => (),
+                    }
                 }
             }
 
-            // transforms coordinates for each content widget
+            // transforms coordinates for the uppermost widget under the mouse
             Event::Mouse(event) => {
-                let (mouse_x, mouse_y) = (event.column as u32, event.row as u32);
-                for widget in self.content.iter_mut() {
-                    //let widget = *widget;
-                    let widget_pos = widget.get_position();
-                    let widget_size = widget.get_size();
-
-                    let mouse_x = mouse_x as f32;
-                    let mouse_y = mouse_y as f32;
-
-                    if mouse_x <= widget_pos.x + widget_size.x
-                        && mouse_x >= widget_pos.x
-                        && mouse_y <= widget_pos.y + widget_size.y
-                        && mouse_y >= widget_pos.y
-                    {
+                let offset = self.scroll_offset();
+                let inner = self.content_box();
+                let on_content = inner.contains(event.column as usize, event.row as usize);
+                let mouse_x = event.column as usize + offset.x;
+                let mouse_y = event.row as usize + offset.y;
+
+                // only the uppermost widget under the mouse is hovered
+                let hovered = self
+                    .draw_order()
+                    .into_iter()
+                    .rev()
+                    .find(|i| self.content[*i].get_rect().contains(mouse_x, mouse_y))
+                    .filter(|_| on_content || self.scroll.is_none());
+                for (i, widget) in self.content.iter_mut().enumerate() {
+                    if Some(i) != hovered {
+                        widget.set_hovered(false);
+                    }
+                }
+                if let Some(i) = hovered {
+                    self.content[i].set_hovered(true);
+                }
+
+                // dividers of split panes are dragged with the left mouse button
+                if let Some(split) = &self.split {
+                    let position = match self.layout_kind() {
+                        ContainerKind::Column => mouse_y,
+                        _ => mouse_x,
+                    };
+                    match (&event.kind, split.dragging) {
+                        (MouseEventKind::Down(MouseButton::Left), _) => {
+                            if let Some(divider) = self.divider_at(mouse_x, mouse_y) {
+                                if let Some(split) = self.split.as_mut() {
+                                    split.dragging = Some(divider);
+                                }
+                                return messages;
+                            }
+                        }
+                        (MouseEventKind::Drag(MouseButton::Left), Some(divider)) => {
+                            self.move_divider(divider, position);
+                            return messages;
+                        }
+                        (MouseEventKind::Up(_), Some(_)) => {
+                            if let Some(split) = self.split.as_mut() {
+                                split.dragging = None;
+                            }
+                            return messages;
+                        }
+                        _ => (),
+                    }
+                }
+
+                // scrolls itself unless a scrollable widget is under the mouse
+                let wheel = match event.kind {
+                    MouseEventKind::ScrollUp => Some(-(SCROLL_STEP as isize)),
+                    MouseEventKind::ScrollDown => Some(SCROLL_STEP as isize),
+                    _ => None,
+                };
+                if let (Some(amount), Some(_)) = (wheel, &self.scroll) {
+                    let nested = self
+                        .draw_order()
+                        .into_iter()
+                        .rev()
+                        .find(|i| self.content[*i].get_rect().contains(mouse_x, mouse_y));
+                    if !nested.is_some_and(|i| Self::is_scrollable(&self.content[i])) {
+                        if event.keymod == KeyMod::Shift {
+                            self.scroll_by(amount, 0);
+                        } else {
+                            self.scroll_by(0, amount);
+                        }
+                        return messages;
+                    }
+                }
+
+                // scrolled out content can not be clicked
+                if self.scroll.is_some() && !on_content {
+                    return messages;
+                }
+
+                for i in self.draw_order().into_iter().rev() {
+                    let widget = &mut self.content[i];
+                    let rect = widget.get_rect();
+
+                    if rect.contains(mouse_x, mouse_y) {
                         let mut msg = widget.handle_input_event(Event::Mouse(MouseEvent {
-                            column: (mouse_x - widget_pos.x) as u16,
-                            row: (mouse_y - widget_pos.y) as u16,
-                            ..event.clone()
+                            column: (mouse_x - rect.x) as u16,
+                            row: (mouse_y - rect.y) as u16,
+                            ..event
                         }));
 
                         messages.append(&mut msg);
+                        break;
                     }
                 }
             }
@@ -220,6 +1112,30 @@ impl Widget for Container {
         messages
     }
 
+    /// lays out the children without any height, so they take only the height they need,
+    /// and adds the border, padding and scrollbars around them
+    fn measure(&self, width: usize) -> Size {
+        let size = Size { x: width, y: 0 };
+        let breakpoint = self.breakpoints.iter().find(|b| b.matches(size));
+        let kind = breakpoint
+            .and_then(|b| b.kind.clone())
+            .unwrap_or_else(|| self.kind.clone());
+        let padding = breakpoint
+            .and_then(|b| b.padding)
+            .unwrap_or(self.style.padding);
+        let hidden = breakpoint.map(|b| b.hidden.clone()).unwrap_or_default();
+
+        let inner = self.content_box_in(size, padding);
+        let rects = self.layout_rects(&kind, inner, &hidden);
+        let content = self.content_extent(&rects, inner);
+        let border = self.border_width();
+        let (bar_x, bar_y) = self.scrollbar_width();
+        Size {
+            x: content.x + border.horizontal() + padding.horizontal() + bar_x,
+            y: content.y + border.vertical() + padding.vertical() + bar_y,
+        }
+    }
+
     fn get_position(&self) -> Position {
         self.position
     }
@@ -243,10 +1159,87 @@ impl Widget for Container {
     fn select(&mut self, selected: bool) {
         self.selected = selected;
 
-        if !self.content.is_empty() {
-            self.content[0].select(selected);
+        // the first widget that is not hidden
+        if let Some(i) = (0..self.content.len()).find(|i| !self.is_hidden(*i)) {
+            self.content[i].select(selected);
+        }
+    }
+
+    /// cursor of the focused widget, hidden when it is scrolled out of view
+    fn get_cursor(&self) -> Option<Cursor> {
+        let offset = self.scroll_offset();
+        let inner = self.content_box();
+        self.draw_order().into_iter().rev().find_map(|i| {
+            let widget = &self.content[i];
+            if !widget.is_selected() {
+                return None;
+            }
+            let cursor = widget.get_cursor()?;
+            let position = widget.get_position();
+            let x = (position.x + cursor.position.x).checked_sub(offset.x)?;
+            let y = (position.y + cursor.position.y).checked_sub(offset.y)?;
+            inner.contains(x, y).then_some(Cursor {
+                position: Position { x, y },
+                ..cursor
+            })
+        })
+    }
+
+    /// popup of the focused widget, hidden when the widget is scrolled out of view
+    fn get_popup(&self) -> Option<Popup> {
+        let offset = self.scroll_offset();
+        let inner = self.content_box();
+        self.draw_order().into_iter().rev().find_map(|i| {
+            let widget = &self.content[i];
+            if !widget.is_selected() {
+                return None;
+            }
+            let popup = widget.get_popup()?;
+            let position = widget.get_position();
+            let x = (position.x + popup.anchor.x).checked_sub(offset.x)?;
+            let y = (position.y + popup.anchor.y).checked_sub(offset.y)?;
+            inner.contains(x, y).then_some(Popup {
+                anchor: Rect { x, y, ..popup.anchor },
+                ..popup
+            })
+        })
+    }
+
+    fn get_state(&self) -> State {
+        State {
+            focused: self.is_selected(),
+            hovered: self.hovered,
+            pressed: false,
+            invalid: false,
+            disabled: self.disabled,
+        }
+    }
+
+    fn set_hovered(&mut self, hovered: bool) {
+        self.hovered = hovered;
+        // the mouse left every child too
+        if !hovered {
+            for widget in self.content.iter_mut() {
+                widget.set_hovered(false);
+            }
+        }
+    }
+
+    /// disables the container together with all of its children
+    fn set_disabled(&mut self, disabled: bool) {
+        self.disabled = disabled;
+        for widget in self.content.iter_mut() {
+            widget.set_disabled(disabled);
         }
     }
+
+    fn get_id(&self) -> Option<String> {
+        self.id.clone()
+    }
+
+    fn get_classes(&self) -> Vec<String> {
+        self.classes.clone()
+    }
 }
 
 impl Style for Container {
@@ -256,60 +1249,518 @@ impl Style for Container {
 
     fn set_style(&mut self, style: StyleSheet) {
         self.style = style;
+        self.base_style = None;
+    }
+
+    fn get_base_style(&self) -> Option<StyleSheet> {
+        self.base_style
+    }
+
+    fn set_base_style(&mut self, style: Option<StyleSheet>) {
+        self.base_style = style;
     }
 
     fn apply_style(&mut self) {
-        // applies position and size for every sub content
-        let mut x_pos: f32 = 0.0; // starting at 1.0 because of borders
-        let mut y_pos: f32 = 0.0;
-        for c in self.content.iter_mut() {
-            //let pos_x = c.get_style().position.0;
-            //let pos_y = c.get_style().position.1;
-            /* start padding */
-            x_pos += c.get_style().padding.x_start;
-            y_pos += c.get_style().padding.y_start;
-
-            let width = c.get_style().width;
-            let height = c.get_style().height;
-
-            let width: f32 = match width {
-                Length::Absolute(x) => x,
-                Length::Relative(x) => {
-                    (self.size.x - 0.0 - x_pos) * x - c.get_style().padding.x_end
+        let breakpoint = self.breakpoints.iter().position(|b| b.matches(self.size));
+        if breakpoint != self.active_breakpoint {
+            self.active_breakpoint = breakpoint;
+            // hidden widgets lose the focus
+            for i in self.hidden() {
+                if let Some(widget) = self.content.get_mut(i) {
+                    widget.select(false);
                 }
-            };
-            let height: f32 = match height {
-                Length::Absolute(y) => y,
-                Length::Relative(y) => {
-                    (self.size.y - 0.0 - y_pos) * y - c.get_style().padding.y_end
+            }
+        }
+        let kind = self.layout_kind();
+        self.fit_ratios();
+
+        // applies position and size for every sub content
+        let inner = self.content_box();
+        let rects = self.layout_rects(&kind, inner, &self.hidden());
+        for (c, rect) in self.content.iter_mut().zip(rects.iter()) {
+            match rect {
+                Some(rect) => {
+                    c.set_position(rect.position());
+                    c.set_size(rect.size());
+                    // request style application of every widget
+                    c.apply_style();
                 }
-            };
+                None => c.set_size(Size::default()),
+            }
+        }
 
-            
-            let wanted_pos: Position = Position{x: x_pos, y: y_pos};
-            let wanted_size: Size = Size{x: width, y: height};
+        let content_size = self.content_extent(&rects, inner);
+        self.update_content_size(content_size);
+    }
+}
 
-            c.set_position(wanted_pos);
-            c.set_size(wanted_size);
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ui::style::{Length, Margin, Padding, Placement, Wrap};
+    use crate::ui::widget::input::Input;
+    use crate::ui::widget::text::Text;
 
-            // request style application of every widget
-            c.apply_style();
+    fn placed(x: Anchor, y: Anchor, z_index: i32) -> Widgets {
+        Widgets::Text(Text::new(
+            "",
+            StyleSheet {
+                width: Length::Absolute(4.0),
+                height: Length::Absolute(2.0),
+                margin: Margin {
+                    x_start: 1,
+                    y_start: 1,
+                    ..Default::default()
+                },
+                placement: Placement { x, y },
+                z_index,
+                ..Default::default()
+            },
+        ))
+    }
 
-            /* creates room for next widget */
-            /* end padding */
-            //x_pos += c.get_style().padding.x_end;
-            //y_pos += c.get_style().padding.y_end;
-            
-            match self.kind {
-                ContainerKind::Row => {
-                    y_pos = 0.0;
-                    x_pos += width;
-                },  
-                ContainerKind::Column => {
-                    x_pos = 0.0;
-                    y_pos += height;
-                }
-            }
+    #[test]
+    fn stack_places_by_anchor_and_draws_by_z_index() {
+        let mut stack = Container::new(
+            ContainerKind::Stack,
+            vec![
+                placed(Anchor::Center, Anchor::Center, 2),
+                placed(Anchor::End(1), Anchor::Start(2), 0),
+                placed(Anchor::Start(0), Anchor::End(0), 1),
+            ],
+            StyleSheet::default(),
+        );
+        stack.set_size(Size { x: 21, y: 11 });
+        stack.apply_style();
+
+        // the margin is kept when centering and at the start
+        let positions: Vec<Position> = stack.content.iter().map(|c| c.get_position()).collect();
+        assert_eq!(positions[0], Position { x: 9, y: 5 });
+        assert_eq!(positions[1], Position { x: 16, y: 3 });
+        assert_eq!(positions[2], Position { x: 1, y: 9 });
+        assert_eq!(stack.draw_order(), [1, 2, 0]);
+    }
+
+    #[test]
+    fn children_are_laid_out_inside_border_and_padding() {
+        let child = |margin: Margin| {
+            Widgets::Text(Text::new(
+                "",
+                StyleSheet {
+                    width: Length::Relative(1.0),
+                    height: Length::Absolute(2.0),
+                    margin,
+                    ..Default::default()
+                },
+            ))
+        };
+        let mut column = Container::new(
+            ContainerKind::Column,
+            vec![child(Margin::uniform(1)), child(Margin::default())],
+            StyleSheet {
+                padding: Padding {
+                    x_start: 2,
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+        )
+        .with_default_borders();
+        column.set_size(Size { x: 20, y: 10 });
+        column.apply_style();
+
+        // border, padding and margin are added up, relative widths exclude them
+        let first = &column.content[0];
+        assert_eq!(first.get_position(), Position { x: 4, y: 2 });
+        assert_eq!(first.get_size(), Size { x: 14, y: 2 });
+        let second = &column.content[1];
+        assert_eq!(second.get_position(), Position { x: 3, y: 5 });
+        assert_eq!(second.get_size(), Size { x: 16, y: 2 });
+    }
+
+    #[test]
+    fn round_span_shares_edges() {
+        assert_eq!(round_span(0.0, 3.4), (0, 3));
+        assert_eq!(round_span(3.4, 3.4), (3, 4));
+        assert_eq!(round_span(6.8, 3.2), (7, 3));
+        assert_eq!(round_span(-2.0, 1.0), (0, 0));
+    }
+
+    #[test]
+    fn fractional_widths_fill_the_row_without_gaps() {
+        let third = || {
+            Widgets::Text(Text::new(
+                "",
+                StyleSheet {
+                    width: Length::Absolute(10.0 / 3.0),
+                    height: Length::Relative(1.0),
+                    ..Default::default()
+                },
+            ))
+        };
+        let mut row = Container::new(
+            ContainerKind::Row,
+            vec![third(), third(), third()],
+            StyleSheet::default(),
+        );
+        row.set_size(Size { x: 10, y: 1 });
+        row.apply_style();
+
+        let spans: Vec<(usize, usize)> = row
+            .content
+            .iter()
+            .map(|c| (c.get_rect().x, c.get_rect().width))
+            .collect();
+        assert_eq!(spans, [(0, 3), (3, 4), (7, 3)]);
+    }
+
+    fn relative(width: f32, height: f32) -> Widgets {
+        Widgets::Text(Text::new(
+            "",
+            StyleSheet {
+                width: Length::Relative(width),
+                height: Length::Relative(height),
+                ..Default::default()
+            },
+        ))
+    }
+
+    #[test]
+    fn breakpoints_change_the_kind_and_hide_widgets() {
+        let mut container = Container::new(
+            ContainerKind::Row,
+            vec![relative(0.5, 1.0), relative(1.0, 1.0), relative(1.0, 1.0)],
+            StyleSheet::default(),
+        )
+        .with_breakpoint(
+            Breakpoint::max_width(20)
+                .with_kind(ContainerKind::Column)
+                .hide(1),
+        );
+
+        container.set_size(Size { x: 40, y: 10 });
+        container.apply_style();
+        let rects: Vec<Rect> = container.content.iter().map(|c| c.get_rect()).collect();
+        assert_eq!((rects[1].x, rects[1].width), (20, 20));
+        assert_eq!(rects[2].width, 0);
+
+        container.set_size(Size { x: 20, y: 10 });
+        container.apply_style();
+        let rects: Vec<Rect> = container.content.iter().map(|c| c.get_rect()).collect();
+        assert_eq!((rects[0].y, rects[0].width, rects[0].height), (0, 10, 10));
+        assert_eq!(rects[1].size(), Size::default());
+        assert_eq!(container.draw_order(), [0, 2]);
+    }
+
+    fn selected(container: &Container) -> Vec<bool> {
+        container.content.iter().map(|c| c.is_selected()).collect()
+    }
+
+    #[test]
+    fn hidden_widgets_lose_the_focus_and_are_skipped() {
+        let mut container = Container::new(
+            ContainerKind::Row,
+            vec![relative(0.3, 1.0), relative(0.3, 1.0), relative(0.3, 1.0)],
+            StyleSheet::default(),
+        )
+        .with_tab_selector()
+        .with_breakpoint(Breakpoint::max_width(20).hide(0).hide(1));
+        container.set_size(Size { x: 40, y: 10 });
+        container.apply_style();
+        container.select(true);
+        container.handle_input_event(Event::Key(KeyMod::None, KeyCode::Tab));
+        assert_eq!(selected(&container), [false, true, false]);
+
+        container.set_size(Size { x: 20, y: 10 });
+        container.apply_style();
+        assert_eq!(selected(&container), [false, false, false]);
+        container.handle_input_event(Event::Key(KeyMod::None, KeyCode::Tab));
+        assert_eq!(selected(&container), [false, false, true]);
+        container.handle_input_event(Event::Key(KeyMod::None, KeyCode::Tab));
+        assert_eq!(selected(&container), [false, false, true]);
+    }
+
+    #[test]
+    fn scrollable_content_is_not_limited_by_the_viewport() {
+        let mut column = Container::new(
+            ContainerKind::Column,
+            vec![relative(1.0, 0.5), relative(1.0, 0.5), relative(1.0, 0.5)],
+            StyleSheet::default(),
+        )
+        .scrollable(false, false);
+        column.set_size(Size { x: 10, y: 10 });
+        column.apply_style();
+
+        let rows: Vec<(usize, usize)> = column
+            .content
+            .iter()
+            .map(|c| (c.get_rect().y, c.get_rect().height))
+            .collect();
+        assert_eq!(rows, [(0, 5), (5, 5), (10, 5)]);
+
+        // scrolling stops at the end of the content
+        column.handle_input_event(Event::Key(KeyMod::None, KeyCode::PageDown));
+        assert_eq!(column.scroll_offset(), Position { x: 0, y: 5 });
+        column.scroll_by(0, -2);
+        assert_eq!(column.scroll_offset(), Position { x: 0, y: 3 });
+    }
+
+    #[test]
+    fn scrolling_follows_the_focus_into_nested_containers() {
+        let line = || {
+            Widgets::Text(Text::new(
+                "",
+                StyleSheet {
+                    width: Length::Relative(1.0),
+                    height: Length::Absolute(3.0),
+                    ..Default::default()
+                },
+            ))
+        };
+        let list = Container::new(
+            ContainerKind::Column,
+            (0..6).map(|_| line()).collect(),
+            StyleSheet {
+                width: Length::Relative(1.0),
+                height: Length::Absolute(18.0),
+                ..Default::default()
+            },
+        )
+        .with_tab_selector();
+        let mut viewport = Container::new(
+            ContainerKind::Column,
+            vec![Widgets::Container(list)],
+            StyleSheet::default(),
+        )
+        .scrollable(false, false);
+        viewport.set_size(Size { x: 10, y: 7 });
+        viewport.apply_style();
+        viewport.select(true);
+
+        let mut offsets = Vec::new();
+        for _ in 0..5 {
+            viewport.handle_input_event(Event::Key(KeyMod::None, KeyCode::Tab));
+            offsets.push(viewport.scroll_offset().y);
         }
+        assert_eq!(offsets, [0, 2, 5, 8, 11]);
+    }
+
+    #[test]
+    fn split_sizes_fill_the_space_by_ratio() {
+        assert_eq!(split_sizes(10, &[1.0, 1.0, 1.0], 0), [3, 4, 3]);
+        assert_eq!(split_sizes(10, &[3.0, 1.0], 0), [8, 2]);
+        assert_eq!(split_sizes(9, &[0.0, 0.0, 0.0], 0), [3, 3, 3]);
+        assert_eq!(split_sizes(10, &[9.0, 1.0], 3), [7, 3]);
+        // the minimum is lowered when it cannot be met
+        assert_eq!(split_sizes(4, &[9.0, 1.0], 3), [2, 2]);
+        assert!(split_sizes(10, &[], 2).is_empty());
+    }
+
+    fn widths(container: &Container) -> Vec<usize> {
+        container
+            .content
+            .iter()
+            .map(|c| c.get_rect().width)
+            .collect()
+    }
+
+    #[test]
+    fn split_panes_share_the_space_of_hidden_panes() {
+        let mut split = Container::new(
+            ContainerKind::Row,
+            vec![relative(1.0, 1.0), relative(1.0, 1.0), relative(1.0, 1.0)],
+            StyleSheet::default(),
+        )
+        .split(2)
+        .with_ratios(vec![0.5, 0.5])
+        .with_breakpoint(Breakpoint::max_width(40).hide(1));
+        split.set_size(Size { x: 31, y: 5 });
+        split.apply_style();
+        assert_eq!(widths(&split), [15, 0, 15]);
+
+        split.move_divider(0, 10);
+        assert_eq!(widths(&split), [10, 0, 20]);
+
+        // panes pushed later get the average share
+        split.push(relative(1.0, 1.0));
+        split.apply_style();
+        assert_eq!(split.split.as_ref().unwrap().ratios.len(), 4);
+    }
+
+    #[test]
+    fn dividers_are_dragged_with_the_mouse() {
+        let mouse = |kind: MouseEventKind, column: u16| {
+            Event::Mouse(MouseEvent {
+                kind,
+                column,
+                row: 1,
+                keymod: KeyMod::None,
+            })
+        };
+        let mut split = Container::new(
+            ContainerKind::Row,
+            vec![relative(1.0, 1.0), relative(1.0, 1.0)],
+            StyleSheet::default(),
+        )
+        .split(3);
+        split.set_size(Size { x: 21, y: 5 });
+        split.apply_style();
+        assert_eq!(widths(&split), [10, 10]);
+
+        split.handle_input_event(mouse(MouseEventKind::Down(MouseButton::Left), 10));
+        split.handle_input_event(mouse(MouseEventKind::Drag(MouseButton::Left), 5));
+        assert_eq!(widths(&split), [5, 15]);
+        // the minimum size is kept
+        split.handle_input_event(mouse(MouseEventKind::Drag(MouseButton::Left), 1));
+        assert_eq!(widths(&split), [3, 17]);
+        split.handle_input_event(mouse(MouseEventKind::Up(MouseButton::Left), 1));
+        split.handle_input_event(mouse(MouseEventKind::Drag(MouseButton::Left), 12));
+        assert_eq!(widths(&split), [3, 17]);
+    }
+
+    #[test]
+    fn flow_wraps_widgets_onto_the_next_line() {
+        let word = |width: f32, height: f32| {
+            Widgets::Text(Text::new(
+                "",
+                StyleSheet {
+                    width: Length::Absolute(width),
+                    height: Length::Absolute(height),
+                    margin: Margin {
+                        x_end: 1,
+                        ..Default::default()
+                    },
+                    ..Default::default()
+                },
+            ))
+        };
+        let mut flow = Container::new(
+            ContainerKind::Flow,
+            vec![
+                word(4.0, 1.0),
+                word(4.0, 2.0),
+                word(4.0, 1.0),
+                word(12.0, 1.0),
+            ],
+            StyleSheet::default(),
+        );
+        flow.set_size(Size { x: 12, y: 10 });
+        flow.apply_style();
+
+        // lines are as high as their highest widget, a widget wider than the line gets its own
+        let positions: Vec<Position> = flow.content.iter().map(|c| c.get_position()).collect();
+        assert_eq!(
+            positions,
+            [
+                Position { x: 0, y: 0 },
+                Position { x: 5, y: 0 },
+                Position { x: 0, y: 2 },
+                Position { x: 0, y: 3 },
+            ]
+        );
+    }
+
+    #[test]
+    fn auto_sizes_are_measured_from_the_content() {
+        let auto = StyleSheet {
+            width: Length::Auto,
+            height: Length::Auto,
+            ..Default::default()
+        };
+        let text = Widgets::Text(Text::new(
+            "one two three",
+            StyleSheet {
+                width: Length::Relative(1.0),
+                wrap: Wrap::Word,
+                ..auto
+            },
+        ));
+        let input = Widgets::Input(Input::new("abc", "", auto));
+        let nested = Container::new(
+            ContainerKind::Column,
+            vec![input],
+            StyleSheet {
+                width: Length::Relative(1.0),
+                ..auto
+            },
+        )
+        .with_default_borders();
+        let mut column = Container::new(
+            ContainerKind::Column,
+            vec![text, Widgets::Container(nested), relative(1.0, 1.0)],
+            StyleSheet::default(),
+        );
+        column.set_size(Size { x: 9, y: 20 });
+        column.apply_style();
+
+        let heights: Vec<usize> = column.content.iter().map(|c| c.get_rect().height).collect();
+        assert_eq!(heights, [2, 3, 15]);
+        if let Widgets::Container(nested) = &column.content[1] {
+            assert_eq!(nested.content[0].get_size(), Size { x: 4, y: 1 });
+        }
+        // without a height relative heights are zero
+        assert_eq!(column.measure(9), Size { x: 9, y: 5 });
+    }
+
+    #[test]
+    fn tab_skips_disabled_widgets() {
+        let mut container = Container::new(
+            ContainerKind::Row,
+            vec![relative(0.3, 1.0), relative(0.3, 1.0), relative(0.3, 1.0)],
+            StyleSheet::default(),
+        )
+        .with_tab_selector();
+        container.content[1].set_disabled(true);
+        container.set_size(Size { x: 30, y: 10 });
+        container.apply_style();
+        container.select(true);
+        assert_eq!(selected(&container), [true, false, false]);
+        container.handle_input_event(Event::Key(KeyMod::None, KeyCode::Tab));
+        assert_eq!(selected(&container), [false, false, true]);
+    }
+
+    #[test]
+    fn cursor_of_the_focused_widget_follows_the_scroll_offset() {
+        let input = || {
+            Widgets::Input(Input::new(
+                "",
+                "",
+                StyleSheet {
+                    width: Length::Relative(1.0),
+                    height: Length::Absolute(3.0),
+                    ..Default::default()
+                },
+            ))
+        };
+        let mut column = Container::new(
+            ContainerKind::Column,
+            vec![input(), input(), input(), input()],
+            StyleSheet {
+                padding: Padding {
+                    x_start: 2,
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+        )
+        .with_tab_selector()
+        .scrollable(false, false);
+        column.set_size(Size { x: 10, y: 7 });
+        column.apply_style();
+        column.select(true);
+        assert_eq!(
+            column.get_cursor().map(|c| c.position),
+            Some(Position { x: 2, y: 0 })
+        );
+
+        column.handle_input_event(Event::Key(KeyMod::None, KeyCode::Tab));
+        assert_eq!(
+            column.get_cursor().map(|c| c.position),
+            Some(Position { x: 2, y: 3 })
+        );
+
+        // scrolled out of view
+        column.scroll_by(0, 5);
+        assert_eq!(column.get_cursor(), None);
     }
 }
@@ -1,7 +1,19 @@
-use super::{Widget, TAB_WIDTH};
-use crate::events::{Event, KeyCode};
-use crate::ui::style::{Style, StyleSheet};
-use crate::{Position, Size};
+use super::{Cursor, CursorShape, Popup, Widget};
+use crate::clipboard;
+use crate::events::{Event, KeyCode, KeyMod, MouseButton, MouseEvent, MouseEventKind};
+use crate::ui::border::{Border, BorderType};
+use crate::ui::cell::{blank_canvas, paint, styled_canvas, Canvas};
+use crate::ui::command_history::CommandHistory;
+use crate::ui::completion::Completion;
+use crate::ui::history::{Edit, History};
+use crate::ui::keymap::{self, Action, Keymap, Vi, ViMode};
+use crate::ui::style::{Attributes, Color, State, Style, StyleSheet};
+use crate::ui::text_layout::{self, GraphemeCell};
+use crate::ui::theme::Role;
+use crate::ui::validation::{self, InvalidInput, Validator, MASK_SLOT};
+use crate::{Position, Rect, Size};
+use std::ops::Range;
+use unicode_segmentation::UnicodeSegmentation;
 
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct Input {
@@ -9,34 +21,99 @@ pub struct Input {
     pub size: Size,
 
     pub style: StyleSheet,
+    // style from before a stylesheet was applied, stylesheets cascade from it
+    base_style: Option<StyleSheet>,
 
     pub placeholder: String,
+    /// should be changed with `set_text`, which keeps the cursor inside of the text
     pub text: String,
+    // grapheme in front of which the cursor is and the other end of the selection
+    caret: usize,
+    anchor: Option<usize>,
+    /// first shown display column
+    pub scroll: usize,
+    // text and cursor before each step
+    edits: History<(String, usize)>,
 
     pub selected: bool,
+    pub disabled: bool,
+    hovered: bool,
+    pub id: Option<String>,
+    pub classes: Vec<String>,
     pub is_password: bool,
+    /// line breaks are never inserted, pasted ones are replaced by spaces
+    pub single_line: bool,
     pub block_tab: bool,
     pub cursor: bool,
+    pub cursor_shape: CursorShape,
+    pub cursor_blinking: bool,
     pub submit_msg: Option<String>,
     pub on_change: String,
+
+    pub validator: Option<Validator>,
+    /// maximum number of graphemes
+    pub max_length: Option<usize>,
+    /// like `____-__-__`, see `validation::MASK_SLOT`
+    pub mask: Option<String>,
+    pub invalid_input: InvalidInput,
+
+    /// submitted values, browsed with up and down and searched with `Ctrl+R`
+    pub history: Option<Box<CommandHistory>>,
+    search: Option<Search>,
+
+    /// keymap of the widget, the global keymap is used when not set
+    pub keymap: Option<Keymap>,
+    vi: Vi,
+
+    pub completion: Option<Completion>,
+    // shown in the popup while not empty
+    suggestions: Vec<String>,
+    highlighted: usize,
 }
 impl Input {
     pub fn new<S: Into<String>>(placeholder: S, message: S, style: StyleSheet) -> Self {
         Self {
-            position: Position { x: 0.0, y: 0.0 },
-            size: Size { x: 0.0, y: 0.0 },
+            position: Position::default(),
+            size: Size::default(),
 
             style,
+            base_style: None,
 
             placeholder: placeholder.into(),
             text: String::new(),
+            caret: 0,
+            anchor: None,
+            scroll: 0,
+            edits: History::new(),
 
             selected: false,
+            disabled: false,
+            hovered: false,
+            id: None,
+            classes: Vec::new(),
             is_password: false,
+            single_line: false,
             block_tab: false,
             cursor: true,
+            cursor_shape: CursorShape::Bar,
+            cursor_blinking: true,
             submit_msg: None,
             on_change: message.into(),
+
+            validator: None,
+            max_length: None,
+            mask: None,
+            invalid_input: InvalidInput::Reject,
+
+            history: None,
+            search: None,
+
+            keymap: None,
+            vi: Vi::default(),
+
+            completion: None,
+            suggestions: Vec::new(),
+            highlighted: 0,
         }
     }
 
@@ -45,13 +122,29 @@ impl Input {
         self
     }
 
+    pub fn with_id<S: Into<String>>(mut self, id: S) -> Self {
+        self.id = Some(id.into());
+        self
+    }
+
+    pub fn with_class<S: Into<String>>(mut self, class: S) -> Self {
+        self.classes.push(class.into());
+        self
+    }
+
     pub fn with_tab_blocker(mut self, block: bool) -> Self {
         self.block_tab = block;
         self
     }
 
     pub fn with_cursor(mut self, cursor: bool) -> Self {
-        self.block_tab = cursor;
+        self.cursor = cursor;
+        self
+    }
+
+    pub fn with_cursor_shape(mut self, shape: CursorShape, blinking: bool) -> Self {
+        self.cursor_shape = shape;
+        self.cursor_blinking = blinking;
         self
     }
 
@@ -60,101 +153,903 @@ impl Input {
         self
     }
 
+    pub fn with_single_line(mut self) -> Self {
+        self.single_line = true;
+        self
+    }
+
+    /// submitted values are added to the history
+    pub fn with_history(mut self, history: CommandHistory) -> Self {
+        self.history = Some(Box::new(history));
+        self
+    }
+
+    pub fn with_keymap(mut self, keymap: Keymap) -> Self {
+        self.keymap = Some(keymap);
+        self
+    }
+
+    /// suggestions are shown below the input while typing, chosen with up, down and tab and accepted with enter.
+    /// the rest of the highlighted one is shown behind the cursor and accepted with right
+    pub fn with_completion(mut self, completion: Completion) -> Self {
+        self.completion = Some(completion);
+        self
+    }
+
+    pub fn get_suggestions(&self) -> &[String] {
+        &self.suggestions
+    }
+
+    fn update_suggestions(&mut self) {
+        let caret = self.caret();
+        let before_cursor = &self.text[..self.byte_index(caret)];
+        self.suggestions = match &self.completion {
+            Some(completion) if !before_cursor.is_empty() => {
+                completion.suggestions(&self.text, caret, before_cursor)
+            }
+            _ => Vec::new(),
+        };
+        self.highlighted = 0;
+    }
+
+    // replaces the text in front of the cursor with the highlighted suggestion
+    fn accept_suggestion(&mut self) {
+        if let Some(suggestion) = self.suggestions.get(self.highlighted).cloned() {
+            self.anchor = Some(0);
+            self.caret = self.caret();
+            self.replace_selection(&suggestion);
+        }
+        self.suggestions.clear();
+    }
+
+    // rest of the highlighted suggestion if it continues the text and the cursor is at its end
+    fn ghost_text(&self) -> Option<&str> {
+        if self.is_password || self.caret() != self.grapheme_count() {
+            return None;
+        }
+        self.suggestions
+            .get(self.highlighted)?
+            .strip_prefix(self.text.as_str())
+            .filter(|rest| !rest.is_empty())
+    }
+
+    pub fn with_validator(mut self, validator: Validator) -> Self {
+        self.validator = Some(validator);
+        self
+    }
+
+    pub fn with_max_length(mut self, length: usize) -> Self {
+        self.max_length = Some(length);
+        self
+    }
+
+    /// slots of the mask are filled by typing, the characters between them are inserted automatically
+    /// and only the slots are checked by the validator
+    pub fn with_mask<S: Into<String>>(mut self, mask: S) -> Self {
+        self.mask = Some(mask.into());
+        self
+    }
+
+    /// invalid edits are flagged with the invalid state instead of being rejected
+    pub fn with_flagged_errors(mut self) -> Self {
+        self.invalid_input = InvalidInput::Flag;
+        self
+    }
+
+    /// whether the text passes the max length, mask and validator, a partly filled mask is valid
+    pub fn is_valid(&self) -> bool {
+        self.accepts(&self.text)
+    }
+
+    fn accepts(&self, text: &str) -> bool {
+        if self
+            .max_length
+            .is_some_and(|max| text.graphemes(true).count() > max)
+        {
+            return false;
+        }
+        let slots = match &self.mask {
+            Some(mask) => match validation::fit_mask(text, mask) {
+                Some(slots) => slots,
+                None => return false,
+            },
+            None => text.to_string(),
+        };
+        self.validator
+            .as_ref()
+            .is_none_or(|validator| validator.validate(&slots))
+    }
+
+    // the typed character with the literals of the mask in front of it
+    fn masked(&self, c: char) -> String {
+        let start = self.selection().map_or(self.caret(), |range| range.start);
+        let (slot, typed) = (MASK_SLOT.to_string(), c.to_string());
+        let mut text = String::new();
+        let mask = self
+            .mask
+            .iter()
+            .flat_map(|mask| mask.graphemes(true).skip(start));
+        for literal in mask.take_while(|m| *m != slot && *m != typed) {
+            text.push_str(literal);
+        }
+        text.push(c);
+        text
+    }
+
     pub fn set_text(&mut self, text: &str) {
         let height = text.matches('\n').count() + 1;
-        let width = text
-            .split('\n')
-            .into_iter()
-            .map(|x| x.len())
-            .max()
-            .unwrap_or(0);
+        let width = text_layout::text_width(text, self.style.tab_width);
 
         self.size = Size {
-            x: width as f32,
-            y: height as f32,
+            x: width,
+            y: height,
         };
         self.text = String::from(text);
+        self.caret = self.grapheme_count();
+        self.anchor = None;
+        self.scroll_to_cursor();
     }
-}
 
-impl Widget for Input {
-    fn to_char_array(&self) -> Vec<Vec<char>> {
-        let mut buffer: Vec<Vec<char>> =
-            vec![vec![' '; self.size.x as usize]; self.size.y as usize];
+    /// graphemes between the cursor and the other end of the selection, none if nothing is selected
+    pub fn selection(&self) -> Option<Range<usize>> {
+        let (caret, anchor) = (self.caret(), self.anchor?.min(self.grapheme_count()));
+        match caret.cmp(&anchor) {
+            std::cmp::Ordering::Less => Some(caret..anchor),
+            std::cmp::Ordering::Greater => Some(anchor..caret),
+            std::cmp::Ordering::Equal => None,
+        }
+    }
 
-        let chars: Vec<char> = if !self.text.is_empty() {
-            self.text.chars().collect()
-        } else {
-            self.placeholder.chars().collect()
-        };
+    pub fn selected_text(&self) -> String {
+        self.selection().map_or_else(String::new, |range| {
+            self.text[self.byte_index(range.start)..self.byte_index(range.end)].to_string()
+        })
+    }
+
+    pub fn select_all(&mut self) {
+        self.anchor = Some(0);
+        self.caret = self.grapheme_count();
+    }
+
+    /// copies the selection into the clipboard, passwords can not be copied
+    pub fn copy(&self) {
+        if !self.is_password && self.selection().is_some() {
+            clipboard::copy(&self.selected_text());
+        }
+    }
+
+    pub fn cut(&mut self) {
+        if !self.is_password && self.selection().is_some() {
+            self.copy();
+            self.replace_selection("");
+        }
+    }
+
+    pub fn paste(&mut self) {
+        self.replace_selection(&clipboard::paste());
+    }
+
+    /// goes back to the text before the last step, returns false if there is nothing to undo
+    pub fn undo(&mut self) -> bool {
+        match self.edits.undo((self.text.clone(), self.caret())) {
+            Some(snapshot) => self.restore(snapshot),
+            None => false,
+        }
+    }
 
-        let mut x: usize = 0;
-        let mut y: usize = 0;
-        for char in chars {
-            match char {
-                '\n' => {
-                    y += 1;
-                    x = 0;
+    pub fn redo(&mut self) -> bool {
+        match self.edits.redo((self.text.clone(), self.caret())) {
+            Some(snapshot) => self.restore(snapshot),
+            None => false,
+        }
+    }
+
+    fn apply_key(&mut self, modifier: KeyMod, code: KeyCode, step: &mut Step) {
+        let extend = modifier == KeyMod::Shift;
+        let caret = self.caret();
+        let (line, x) = self.locate(caret);
+        let last_line = self.locate(self.grapheme_count()).0;
+        let selecting = self.selection().is_some();
+        let suggesting = !self.suggestions.is_empty();
+        let count = self.suggestions.len().max(1);
+        match code {
+            KeyCode::Down | KeyCode::Tab if suggesting => {
+                self.highlighted = (self.highlighted + 1) % count;
+            }
+            KeyCode::Up | KeyCode::BackTab if suggesting => {
+                self.highlighted = (self.highlighted + count - 1) % count;
+            }
+            KeyCode::Enter if suggesting => {
+                self.accept_suggestion();
+                step.complete = false;
+            }
+            KeyCode::Right if self.ghost_text().is_some() && !extend => {
+                self.accept_suggestion();
+                step.complete = false;
+            }
+            KeyCode::Esc if suggesting => self.suggestions.clear(),
+            KeyCode::Char('z') if modifier == KeyMod::Control => {
+                self.undo();
+                step.edit = None;
+            }
+            KeyCode::Char('y') if modifier == KeyMod::Control => {
+                self.redo();
+                step.edit = None;
+            }
+            KeyCode::Enter => {
+                if let Some(msg) = self.submit_msg.clone() {
+                    if let Some(history) = self.history.as_mut() {
+                        history.push(&self.text);
+                    }
+                    step.submit = Some(msg);
+                } else if !self.single_line {
+                    self.replace_selection("\n");
+                }
+            }
+            KeyCode::Char('r') if modifier == KeyMod::Control && self.history.is_some() => {
+                self.search = Some(Search::default());
+            }
+            // the history is browsed from the first and the last line
+            KeyCode::Up if self.history.is_some() && (self.single_line || line == 0) => {
+                let current = self.text.clone();
+                if let Some(entry) = self.history.as_mut().and_then(|h| h.older(&current)) {
+                    self.replace_text(entry);
+                }
+            }
+            KeyCode::Down if self.history.is_some() && (self.single_line || line == last_line) => {
+                if let Some(entry) = self.history.as_mut().and_then(|h| h.newer()) {
+                    self.replace_text(entry);
                 }
-                '\t' => {
-                    x += TAB_WIDTH;
+            }
+            KeyCode::Up if line > 0 => self.move_to(self.index_at(line - 1, x), extend),
+            KeyCode::Down if line < last_line => self.move_to(self.index_at(line + 1, x), extend),
+            KeyCode::Tab if !self.block_tab => self.replace_selection("\t"),
+            KeyCode::Char('a') if modifier == KeyMod::Control => self.select_all(),
+            KeyCode::Char('c') if modifier == KeyMod::Control => self.copy(),
+            KeyCode::Char('x') if modifier == KeyMod::Control => self.cut(),
+            KeyCode::Char('v') if modifier == KeyMod::Control => self.paste(),
+            KeyCode::Char(_) if modifier == KeyMod::Control => (),
+            KeyCode::Char(c) => {
+                if !selecting {
+                    step.edit = Some(Edit::Insert);
                 }
-                char => {
-                    if (x as f32) < self.size.x.floor() && (y as f32) < self.size.y.floor() {
-                        if !self.is_password {
-                            buffer[y][x] = char;
-                        } else {
-                            buffer[y][x] = '*';
-                        }
+                self.replace_selection(&self.masked(c));
+            }
+            // removes the whole grapheme cluster
+            KeyCode::Backspace | KeyCode::Delete => {
+                if !selecting {
+                    step.edit = Some(Edit::Remove);
+                }
+                self.remove(code == KeyCode::Delete);
+            }
+            // without shift the selection collapses to its side
+            KeyCode::Left => match self.selection() {
+                Some(range) if !extend => self.move_to(range.start, false),
+                _ => self.move_to(caret.saturating_sub(1), extend),
+            },
+            KeyCode::Right => match self.selection() {
+                Some(range) if !extend => self.move_to(range.end, false),
+                _ => self.move_to(caret + 1, extend),
+            },
+            KeyCode::Home => self.move_to(self.line_start(line), extend),
+            KeyCode::End => self.move_to(self.line_end(line), extend),
+            _ => (),
+        }
+    }
+
+    fn apply_action(&mut self, action: Action, step: &mut Step) {
+        match action {
+            Action::Key(modifier, code) => self.apply_key(modifier, code, step),
+            Action::Move(motion) => {
+                let graphemes: Vec<&str> = self.text.graphemes(true).collect();
+                self.move_to(keymap::target(&graphemes, self.caret(), motion), false);
+            }
+            Action::Cut(motion) => {
+                let graphemes: Vec<&str> = self.text.graphemes(true).collect();
+                let range = keymap::motion_range(&graphemes, self.caret(), motion);
+                if let Some(text) = keymap::copied_text(&graphemes, self.caret(), motion) {
+                    if !self.is_password {
+                        clipboard::copy(&text);
                     }
-                    x += 1;
+                    self.anchor = Some(range.start);
+                    self.caret = range.end;
+                    self.replace_selection("");
+                }
+            }
+            Action::Copy(motion) => {
+                let graphemes: Vec<&str> = self.text.graphemes(true).collect();
+                let text = keymap::copied_text(&graphemes, self.caret(), motion);
+                if let Some(text) = text.filter(|_| !self.is_password) {
+                    clipboard::copy(&text);
+                }
+            }
+            Action::Paste { after, lines } => {
+                let graphemes: Vec<&str> = self.text.graphemes(true).collect();
+                let (index, text, caret) =
+                    keymap::paste_at(&graphemes, self.caret(), &clipboard::paste(), after, lines);
+                self.move_to(index, false);
+                self.replace_selection(&text);
+                self.move_to(caret, false);
+            }
+            Action::Undo => {
+                self.undo();
+                step.edit = None;
+            }
+            Action::Redo => {
+                self.redo();
+                step.edit = None;
+            }
+            Action::Submit if !self.suggestions.is_empty() || self.submit_msg.is_some() => {
+                self.apply_key(KeyMod::None, KeyCode::Enter, step);
+            }
+            Action::Complete if !self.suggestions.is_empty() => {
+                self.apply_key(KeyMod::None, KeyCode::Tab, step);
+            }
+            Action::Complete if self.completion.is_some() => {
+                self.update_suggestions();
+                step.complete = false;
+            }
+            Action::Submit | Action::Complete => (),
+        }
+    }
+
+    /// mode of the vi keymap, none if the widget uses another keymap
+    pub fn vi_mode(&self) -> Option<ViMode> {
+        (self.keymap.unwrap_or_else(keymap::global) == Keymap::Vi).then_some(self.vi.mode)
+    }
+
+    // replaces the whole text with the cursor at its end
+    fn replace_text(&mut self, text: String) {
+        self.caret = text.graphemes(true).count();
+        self.text = text;
+        self.anchor = None;
+    }
+
+    // text of the search line and the width of the part in front of the match
+    fn search_line(&self, search: &Search) -> (String, usize) {
+        let prompt = format!("(reverse-i-search)`{}': ", search.query);
+        let found = search
+            .found
+            .and_then(|i| self.history.as_ref()?.entries.get(i))
+            .map_or("", |entry| entry.lines().next().unwrap_or(""));
+        let width = text_layout::text_width(&prompt, self.style.tab_width);
+        (prompt + found, width)
+    }
+
+    // keys while searching the history, false if the key accepts the match and is handled as usual
+    fn handle_search_key(&mut self, modifier: &KeyMod, code: &KeyCode) -> bool {
+        let (Some(search), Some(history)) = (self.search.as_mut(), self.history.as_ref()) else {
+            return false;
+        };
+        let newest = history.entries.len();
+        match code {
+            // older matches of the same query
+            KeyCode::Char('r') if *modifier == KeyMod::Control => {
+                let before = search.found.unwrap_or(newest);
+                if let Some(found) = history.search(&search.query, before) {
+                    search.found = Some(found);
+                }
+            }
+            KeyCode::Char('g') if *modifier == KeyMod::Control => self.search = None,
+            KeyCode::Esc => self.search = None,
+            KeyCode::Char(c) if *modifier != KeyMod::Control => {
+                search.query.push(*c);
+                let before = search.found.map_or(newest, |found| found + 1);
+                search.found = history.search(&search.query, before);
+            }
+            KeyCode::Backspace => {
+                search.query.pop();
+                search.found = history.search(&search.query, newest);
+            }
+            _ => {
+                let found = search.found.and_then(|i| history.entries.get(i)).cloned();
+                self.search = None;
+                if let Some(entry) = found {
+                    self.replace_text(entry);
+                }
+                return false;
+            }
+        }
+        true
+    }
+
+    fn restore(&mut self, (text, caret): (String, usize)) -> bool {
+        self.text = text;
+        self.caret = caret;
+        self.anchor = None;
+        self.scroll_to_cursor();
+        true
+    }
+
+    fn grapheme_count(&self) -> usize {
+        self.text.graphemes(true).count()
+    }
+
+    // the text may have been changed without `set_text`
+    fn caret(&self) -> usize {
+        self.caret.min(self.grapheme_count())
+    }
+
+    fn byte_index(&self, index: usize) -> usize {
+        self.text
+            .grapheme_indices(true)
+            .nth(index)
+            .map_or(self.text.len(), |(i, _)| i)
+    }
+
+    // replaces the selection or inserts at the cursor, which is then placed behind the new text
+    fn replace_selection(&mut self, text: &str) {
+        let text = match self.single_line {
+            true => text.lines().collect::<Vec<&str>>().join(" "),
+            false => text.to_string(),
+        };
+        let caret = self.caret();
+        let range = self.selection().unwrap_or(caret..caret);
+        let (start, end) = (self.byte_index(range.start), self.byte_index(range.end));
+        self.text.replace_range(start..end, &text);
+        // a combining character joins the grapheme in front of the selection
+        self.caret = self.text[..start + text.len()].graphemes(true).count();
+        self.anchor = None;
+    }
+
+    // removes the selection or the grapheme in front of or behind the cursor
+    fn remove(&mut self, forward: bool) {
+        let caret = self.caret();
+        if self.selection().is_none() {
+            self.anchor = match forward {
+                true if caret < self.grapheme_count() => Some(caret + 1),
+                false if caret > 0 => Some(caret - 1),
+                _ => return,
+            };
+        }
+        self.replace_selection("");
+    }
+
+    // the selection is extended if `extend` is set and removed otherwise
+    fn move_to(&mut self, index: usize, extend: bool) {
+        if extend {
+            self.anchor.get_or_insert(self.caret());
+        } else {
+            self.anchor = None;
+        }
+        self.caret = index.min(self.grapheme_count());
+    }
+
+    // area inside of the padding, without the mode indicator of the vi keymap
+    fn text_area(&self) -> Rect {
+        let area = Rect::new(Position::default(), self.size).inset(self.style.padding);
+        match self.vi_mode() {
+            Some(_) => keymap::split_mode_indicator(area).0,
+            None => area,
+        }
+    }
+
+    fn scroll_to_cursor(&mut self) {
+        let width = self.text_area().width;
+        let (_, x) = self.locate(self.caret());
+        let end = self
+            .grapheme_cells()
+            .iter()
+            .map(|cell| cell.x + cell.width)
+            .max()
+            .unwrap_or(0);
+
+        // a cell in front of and behind the cursor stays visible for the overflow indicators
+        let margin = usize::from(width > 2);
+        if x < self.scroll + margin {
+            self.scroll = x.saturating_sub(margin);
+        } else if width > 0 && x + 1 + margin > self.scroll + width {
+            self.scroll = x + 1 + margin - width;
+        }
+        // no space is wasted behind the end of the text, the cursor takes a cell there
+        self.scroll = self.scroll.min((end + 1).saturating_sub(width));
+    }
+
+    fn grapheme_cells(&self) -> Vec<GraphemeCell<'_>> {
+        text_layout::grapheme_cells(&self.text, self.style.tab_width, self.is_password)
+    }
+
+    // line and display column of the grapheme at `index`
+    fn locate(&self, index: usize) -> (usize, usize) {
+        let cells = self.grapheme_cells();
+        match (cells.get(index), cells.last()) {
+            (Some(cell), _) => (cell.line, cell.x),
+            (None, Some(last)) if self.text.ends_with('\n') => (last.line + 1, 0),
+            (None, Some(last)) => (last.line, last.x + last.width),
+            (None, None) => (0, 0),
+        }
+    }
+
+    // index of the grapheme at display column `x` of `line`, or the end of the line
+    fn index_at(&self, line: usize, x: usize) -> usize {
+        let cells = self.grapheme_cells();
+        cells
+            .iter()
+            .position(|cell| cell.line == line && (cell.x + cell.width > x || cell.width == 0))
+            .unwrap_or(cells.len())
+    }
+
+    fn line_start(&self, line: usize) -> usize {
+        let cells = self.grapheme_cells();
+        cells
+            .iter()
+            .position(|cell| cell.line == line)
+            .unwrap_or(cells.len())
+    }
+
+    // in front of the line break
+    fn line_end(&self, line: usize) -> usize {
+        let cells = self.grapheme_cells();
+        cells
+            .iter()
+            .position(|cell| cell.line > line)
+            .map_or(cells.len(), |i| i - 1)
+    }
+}
+
+impl Widget for Input {
+    fn to_cell_array(&self) -> Canvas {
+        let style = self.style.for_state(self.get_state());
+        let mut buffer: Canvas = styled_canvas(self.size, &style);
+        // the placeholder uses its own role unless the state or style sets a color
+        if self.text.is_empty() && style.foreground.is_none() {
+            let full = Rect::new(Position::default(), self.size);
+            paint(
+                &mut buffer,
+                full,
+                Some(Color::Role(Role::Placeholder)),
+                None,
+            );
+        }
+
+        // text is drawn inside of the padding
+        let area = self.text_area();
+        let padded = Rect::new(Position::default(), self.size).inset(self.style.padding);
+        if let (Some(mode), (_, Some(indicator))) =
+            (self.vi_mode(), keymap::split_mode_indicator(padded))
+        {
+            keymap::draw_mode_indicator(&mut buffer, indicator, mode, style.foreground.is_some());
+        }
+        if let (Some(search), true) = (&self.search, area.height > 0) {
+            let (line, prompt) = self.search_line(search);
+            let graphemes: Vec<&str> = line.graphemes(true).collect();
+            text_layout::draw_graphemes(&mut buffer[area.y], area.x, area.right(), &graphemes);
+            if style.foreground.is_none() {
+                let prompt = Rect {
+                    width: prompt,
+                    height: 1,
+                    ..area
+                };
+                paint(
+                    &mut buffer,
+                    prompt.intersection(area),
+                    Some(Color::Role(Role::Placeholder)),
+                    None,
+                );
+            }
+            return buffer;
+        }
+        let cells = self.grapheme_cells();
+        if self.text.is_empty() {
+            let lines = text_layout::split_lines(&self.placeholder, style.tab_width);
+            for (i, line) in lines.iter().enumerate().take(area.height) {
+                text_layout::draw_graphemes(&mut buffer[area.y + i], area.x, area.right(), line);
+            }
+        }
+        for y in 0..area.height {
+            let row = &mut buffer[area.y + y];
+            text_layout::draw_cells(row, &cells, y, self.scroll, area.x, area.right());
+        }
+
+        // the unfilled rest of the mask is shown behind the text
+        if let Some(mask) = self
+            .mask
+            .as_ref()
+            .filter(|_| self.placeholder.is_empty() || !self.text.is_empty())
+        {
+            let rest: Vec<&str> = mask.graphemes(true).skip(self.grapheme_count()).collect();
+            let (line, x) = self.locate(self.grapheme_count());
+            if line < area.height && x >= self.scroll && !rest.is_empty() {
+                let (x, y) = (area.x + x - self.scroll, area.y + line);
+                text_layout::draw_graphemes(&mut buffer[y], x, area.right(), &rest);
+                if style.foreground.is_none() {
+                    let hint = Rect {
+                        x,
+                        y,
+                        width: text_layout::line_width(&rest),
+                        height: 1,
+                    };
+                    paint(
+                        &mut buffer,
+                        hint.intersection(area),
+                        Some(Color::Role(Role::Placeholder)),
+                        None,
+                    );
                 }
             }
         }
 
-        // cursor
-        if self.is_selected() && self.cursor && buffer.len() > y && buffer[y].len() > x {
-            buffer[y][x] = '▉';
+        if let Some(ghost) = self.ghost_text() {
+            let (line, x) = self.locate(self.grapheme_count());
+            if line < area.height && x >= self.scroll {
+                let ghost: Vec<&str> = ghost.graphemes(true).collect();
+                let (x, y) = (area.x + x - self.scroll, area.y + line);
+                text_layout::draw_graphemes(&mut buffer[y], x, area.right(), &ghost);
+                let hint = Rect {
+                    x,
+                    y,
+                    width: text_layout::line_width(&ghost),
+                    height: 1,
+                };
+                paint(
+                    &mut buffer,
+                    hint.intersection(area),
+                    Some(Color::Role(Role::Placeholder)),
+                    None,
+                );
+            }
+        }
+
+        if let Some(range) = self.selection() {
+            for cell in cells[range].iter().filter(|cell| cell.x >= self.scroll) {
+                let cell = Rect {
+                    x: area.x + cell.x - self.scroll,
+                    y: area.y + cell.line,
+                    width: cell.width,
+                    height: 1,
+                };
+                paint(
+                    &mut buffer,
+                    cell.intersection(area),
+                    None,
+                    Some(Color::Role(Role::Selection)),
+                );
+            }
+        }
+
+        // `…` at the edges of lines that continue outside of the widget
+        if area.width > 0 {
+            for y in 0..area.height {
+                let mut line = cells.iter().filter(|cell| cell.line == y);
+                if line
+                    .clone()
+                    .any(|cell| cell.x < self.scroll && cell.width > 0)
+                {
+                    buffer[area.y + y][area.x].symbol = String::from("…");
+                }
+                if line.any(|cell| cell.x + cell.width > self.scroll + area.width) {
+                    buffer[area.y + y][area.right() - 1].symbol = String::from("…");
+                }
+            }
         }
 
         buffer
     }
 
+    fn get_cursor(&self) -> Option<Cursor> {
+        if !self.is_selected() || !self.cursor || self.disabled {
+            return None;
+        }
+
+        let area = self.text_area();
+        let (x, y) = match &self.search {
+            // behind the query while searching, the search line is not scrolled
+            Some(search) => {
+                let (_, prompt) = self.search_line(search);
+                (area.x + prompt - "': ".len(), area.y)
+            }
+            None => {
+                let (line, column) = self.locate(self.caret());
+                ((area.x + column).checked_sub(self.scroll)?, area.y + line)
+            }
+        };
+        if !area.contains(x, y) {
+            return None;
+        }
+        // a block on the grapheme in the normal mode of the vi keymap
+        let shape = match self.vi_mode() {
+            Some(ViMode::Normal) if self.search.is_none() => CursorShape::Block,
+            _ => self.cursor_shape,
+        };
+        Some(Cursor {
+            position: Position { x, y },
+            shape,
+            blinking: self.cursor_blinking,
+        })
+    }
+
+    /// suggestions below the input with a border
+    fn get_popup(&self) -> Option<Popup> {
+        if !self.is_selected() || self.disabled || self.suggestions.is_empty() {
+            return None;
+        }
+
+        let shown = self
+            .completion
+            .as_ref()
+            .map_or(0, |completion| completion.max_shown)
+            .min(self.suggestions.len());
+        // `max_shown` of 0 hides the popup, the ghost text is still shown
+        if shown == 0 {
+            return None;
+        }
+        // keeps the highlighted suggestion in view
+        let first = (self.highlighted + 1).saturating_sub(shown);
+        let rows: Vec<Vec<&str>> = self.suggestions[first..first + shown]
+            .iter()
+            .map(|suggestion| {
+                suggestion
+                    .lines()
+                    .next()
+                    .unwrap_or("")
+                    .graphemes(true)
+                    .collect()
+            })
+            .collect();
+        let width = rows
+            .iter()
+            .map(|row| text_layout::line_width(row))
+            .max()
+            .unwrap_or(0);
+
+        let mut canvas: Canvas = blank_canvas(Size {
+            x: width + 4,
+            y: shown + 2,
+        });
+        for (i, row) in rows.iter().enumerate() {
+            text_layout::draw_graphemes(&mut canvas[i + 1], 2, width + 2, row);
+        }
+        let highlighted = Rect {
+            x: 1,
+            y: self.highlighted - first + 1,
+            width: width + 2,
+            height: 1,
+        };
+        paint(
+            &mut canvas,
+            highlighted,
+            None,
+            Some(Color::Role(Role::Selection)),
+        );
+        Border::plain().draw(
+            &mut canvas,
+            BorderType::Plain,
+            Color::Role(Role::Border),
+            Attributes::default(),
+        );
+
+        Some(Popup {
+            anchor: Rect::new(Position::default(), self.size),
+            canvas,
+        })
+    }
+
     fn handle_input_event(&mut self, event: Event) -> Vec<Vec<String>> {
+        if self.disabled {
+            return Vec::new();
+        }
+
+        let (text, caret) = (self.text.clone(), self.caret());
+        if let Event::Key(modifier, code) = &event {
+            if self.handle_search_key(modifier, code) {
+                return vec![];
+            }
+        }
+
+        let mut step = Step::new();
         match event {
-            Event::Key(_modifier, code) => match code {
-                KeyCode::Enter => {
-                    if let Some(msg) = self.submit_msg.clone() {
-                        return vec![vec![String::from(msg)]];
-                    } else {
-                        self.text.push('\n');
-
-                        return vec![vec![String::from(self.on_change.clone()), String::from(&self.text)]];
+            Event::Key(modifier, code) => {
+                let actions = match self.keymap.unwrap_or_else(keymap::global) {
+                    Keymap::Default => vec![Action::Key(modifier, code)],
+                    Keymap::Emacs => keymap::emacs(&modifier, &code),
+                    Keymap::Vi => {
+                        // a change is undone in one step, from its command until escape
+                        let mode = self.vi.mode;
+                        let actions = self.vi.handle(&modifier, &code);
+                        match (mode, self.vi.mode) {
+                            (ViMode::Normal, ViMode::Insert) => self.edits.begin_change(),
+                            (ViMode::Insert, ViMode::Normal) => self.edits.end_change(),
+                            _ => (),
+                        }
+                        actions
                     }
+                };
+                for action in actions {
+                    self.apply_action(action, &mut step);
                 }
-                KeyCode::Tab => {
-                    if !self.block_tab {
-                        self.text.push('\t');
-
-                        return vec![vec![String::from(self.on_change.clone()), String::from(&self.text)]];
-                    }
+                // the cursor of the normal mode is on a grapheme, not behind the line
+                if self.vi_mode() == Some(ViMode::Normal) && self.selection().is_none() {
+                    let graphemes: Vec<&str> = self.text.graphemes(true).collect();
+                    self.caret = keymap::clamp_normal_cursor(&graphemes, self.caret);
                 }
-                KeyCode::Char(c) => {
-                    self.text.push(c);
+            }
 
-                    return vec![vec![String::from(self.on_change.clone()), String::from(&self.text)]];
+            // selects by dragging from where the left button went down
+            Event::Mouse(MouseEvent {
+                kind,
+                column,
+                row,
+                keymod,
+            }) => {
+                let area = self.text_area();
+                let x = (column as usize).saturating_sub(area.x) + self.scroll;
+                let y = (row as usize).saturating_sub(area.y);
+                let index = self.index_at(y, x);
+                match kind {
+                    MouseEventKind::Down(MouseButton::Left) => {
+                        self.move_to(index, keymod == KeyMod::Shift);
+                        self.anchor.get_or_insert(index);
+                    }
+                    MouseEventKind::Drag(MouseButton::Left) => self.move_to(index, true),
+                    _ => (),
                 }
-                KeyCode::Backspace => {
-                    self.text.pop();
+            }
+            Event::Resize(_) => (),
+        }
+        self.scroll_to_cursor();
 
-                    return vec![vec![String::from(self.on_change.clone()), String::from(&self.text)]];
-                }
-                _ => (),
-            },
-            _ => (),
+        let submit: Vec<Vec<String>> = step.submit.into_iter().map(|msg| vec![msg]).collect();
+        if self.text == text {
+            // moving the cursor ends the current step and closes the suggestions
+            if self.caret() != caret {
+                self.edits.break_group();
+                self.suggestions.clear();
+            }
+            return submit;
+        }
+        // undo and redo only go back to accepted text
+        if step.edit.is_some()
+            && self.invalid_input == InvalidInput::Reject
+            && !self.accepts(&self.text)
+        {
+            self.text = text;
+            self.caret = caret;
+            self.anchor = None;
+            self.scroll_to_cursor();
+            return vec![];
+        }
+        if let Some(edit) = step.edit {
+            self.edits.record((text, caret), edit);
         }
+        if step.complete {
+            self.update_suggestions();
+        }
+        let mut messages = vec![vec![self.on_change.clone(), self.text.clone()]];
+        messages.extend(submit);
+        messages
+    }
 
-        vec![]
+    /// lines of the text or the placeholder, with a cell for the cursor behind them
+    fn measure(&self, _width: usize) -> Size {
+        let shown = if self.text.is_empty() {
+            &self.placeholder
+        } else {
+            &self.text
+        };
+        let mut width = if self.is_password {
+            shown
+                .split('\n')
+                .map(|line| line.graphemes(true).count())
+                .max()
+                .unwrap_or(0)
+        } else {
+            text_layout::text_width(shown, self.style.tab_width)
+        };
+        if let Some(mask) = &self.mask {
+            width = width.max(text_layout::text_width(mask, self.style.tab_width));
+        }
+        if self.vi_mode().is_some() {
+            width += keymap::MODE_INDICATOR_WIDTH;
+        }
+        let height = if self.single_line {
+            1
+        } else {
+            shown.matches('\n').count() + 1
+        };
+        Size {
+            x: width + 1 + self.style.padding.horizontal(),
+            y: height + self.style.padding.vertical(),
+        }
     }
 
     fn get_position(&self) -> Position {
@@ -171,6 +1066,7 @@ impl Widget for Input {
 
     fn set_size(&mut self, size: Size) {
         self.size = size;
+        self.scroll_to_cursor();
     }
 
     fn is_selected(&self) -> bool {
@@ -179,6 +1075,32 @@ impl Widget for Input {
     fn select(&mut self, selected: bool) {
         self.selected = selected
     }
+
+    fn get_state(&self) -> State {
+        State {
+            focused: self.selected,
+            hovered: self.hovered,
+            pressed: false,
+            invalid: self.invalid_input == InvalidInput::Flag && !self.is_valid(),
+            disabled: self.disabled,
+        }
+    }
+
+    fn set_hovered(&mut self, hovered: bool) {
+        self.hovered = hovered;
+    }
+
+    fn set_disabled(&mut self, disabled: bool) {
+        self.disabled = disabled;
+    }
+
+    fn get_id(&self) -> Option<String> {
+        self.id.clone()
+    }
+
+    fn get_classes(&self) -> Vec<String> {
+        self.classes.clone()
+    }
 }
 
 impl Style for Input {
@@ -188,8 +1110,429 @@ impl Style for Input {
 
     fn set_style(&mut self, style: StyleSheet) {
         self.style = style;
+        self.base_style = None;
+    }
+
+    fn get_base_style(&self) -> Option<StyleSheet> {
+        self.base_style
+    }
+
+    fn set_base_style(&mut self, style: Option<StyleSheet>) {
+        self.base_style = style;
+    }
+
+    fn apply_style(&mut self) {}
+}
+
+// reverse incremental search through the command history
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+struct Search {
+    query: String,
+    // index of the shown entry
+    found: Option<usize>,
+}
+
+// outcome of the keys of one event
+struct Step {
+    // kind of the edit for the undo history, none for undo and redo themselves
+    edit: Option<Edit>,
+    submit: Option<String>,
+    // whether the suggestions are updated for the new text
+    complete: bool,
+}
+impl Step {
+    fn new() -> Self {
+        Self {
+            // typing and removing single graphemes are grouped into one step
+            edit: Some(Edit::Other),
+            submit: None,
+            complete: true,
+        }
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ui::style::Padding;
+
+    #[test]
+    fn cursor_is_placed_at_the_caret() {
+        let mut input = Input::new(
+            "",
+            "",
+            StyleSheet {
+                padding: Padding {
+                    x_start: 1,
+                    x_end: 1,
+                    y_start: 1,
+                    y_end: 1,
+                },
+                ..Default::default()
+            },
+        );
+        input.set_size(Size { x: 10, y: 4 });
+        for c in "ab\nc".chars() {
+            let code = match c {
+                '\n' => KeyCode::Enter,
+                c => KeyCode::Char(c),
+            };
+            key(&mut input, KeyMod::None, code);
+        }
+        assert_eq!(input.get_cursor(), None);
+
+        input.select(true);
+        let cursor = input.get_cursor().unwrap();
+        assert_eq!(cursor.position, Position { x: 2, y: 2 });
+        assert_eq!(cursor.shape, CursorShape::Bar);
+        key(&mut input, KeyMod::None, KeyCode::Left);
+        key(&mut input, KeyMod::None, KeyCode::Left);
+        assert_eq!(
+            input.get_cursor().unwrap().position,
+            Position { x: 3, y: 1 }
+        );
+
+        // the text scrolls to keep the cursor in view
+        key(&mut input, KeyMod::None, KeyCode::Home);
+        for c in "abcdef".chars() {
+            key(&mut input, KeyMod::None, KeyCode::Char(c));
+        }
+        key(&mut input, KeyMod::None, KeyCode::End);
+        assert_eq!(
+            input.get_cursor().unwrap().position,
+            Position { x: 8, y: 1 }
+        );
+        input.cursor = false;
+        key(&mut input, KeyMod::None, KeyCode::Home);
+        assert_eq!(input.get_cursor(), None);
+    }
+
+    fn key(input: &mut Input, modifier: KeyMod, code: KeyCode) -> Vec<Vec<String>> {
+        input.handle_input_event(Event::Key(modifier, code))
+    }
+
+    fn mouse(input: &mut Input, kind: MouseEventKind, column: u16, row: u16) {
+        input.handle_input_event(Event::Mouse(MouseEvent {
+            kind,
+            column,
+            row,
+            keymod: KeyMod::None,
+        }));
+    }
+
+    #[test]
+    fn shift_extends_the_selection_and_typing_replaces_it() {
+        let mut input = Input::new("", "change", StyleSheet::default());
+        input.set_text("hello world");
+        key(&mut input, KeyMod::None, KeyCode::Home);
+        key(&mut input, KeyMod::Shift, KeyCode::Right);
+        key(&mut input, KeyMod::Shift, KeyCode::Right);
+        assert_eq!(input.selection(), Some(0..2));
+        assert_eq!(input.selected_text(), "he");
+
+        // without shift the selection collapses to its side
+        key(&mut input, KeyMod::None, KeyCode::Left);
+        assert_eq!(input.selection(), None);
+        key(&mut input, KeyMod::Shift, KeyCode::End);
+        let msg = key(&mut input, KeyMod::None, KeyCode::Char('x'));
+        assert_eq!(input.text, "x");
+        assert_eq!(msg, [["change", "x"]]);
+
+        input.set_text("ab\ncd");
+        key(&mut input, KeyMod::Control, KeyCode::Char('a'));
+        key(&mut input, KeyMod::None, KeyCode::Backspace);
+        assert_eq!(input.text, "");
+    }
+
+    #[test]
+    fn selects_by_dragging_the_mouse() {
+        let mut input = Input::new("", "", StyleSheet::default());
+        input.set_text("one\ntwo");
+        input.set_size(Size { x: 10, y: 2 });
+        mouse(&mut input, MouseEventKind::Down(MouseButton::Left), 1, 0);
+        mouse(&mut input, MouseEventKind::Drag(MouseButton::Left), 2, 1);
+        assert_eq!(input.selected_text(), "ne\ntw");
+    }
+
+    #[test]
+    fn copies_cuts_and_pastes() {
+        let _clipboard = clipboard::lock_for_test();
+        let mut input = Input::new("", "", StyleSheet::default());
+        input.set_text("copy me");
+        key(&mut input, KeyMod::Shift, KeyCode::Left);
+        key(&mut input, KeyMod::Shift, KeyCode::Left);
+        key(&mut input, KeyMod::Control, KeyCode::Char('x'));
+        assert_eq!(input.text, "copy ");
+        key(&mut input, KeyMod::Control, KeyCode::Char('v'));
+        key(&mut input, KeyMod::Control, KeyCode::Char('v'));
+        assert_eq!(input.text, "copy meme");
+
+        // passwords can not be copied
+        let mut password = Input::new("", "", StyleSheet::default()).with_password();
+        password.set_text("secret");
+        key(&mut password, KeyMod::Control, KeyCode::Char('a'));
+        key(&mut password, KeyMod::Control, KeyCode::Char('c'));
+        key(&mut input, KeyMod::Control, KeyCode::Char('v'));
+        assert_eq!(input.text, "copy mememe");
+    }
+
+    #[test]
+    fn typing_is_undone_in_steps() {
+        let mut input = Input::new("", "", StyleSheet::default());
+        for c in "ab".chars() {
+            key(&mut input, KeyMod::None, KeyCode::Char(c));
+        }
+        // moving the cursor ends the step
+        key(&mut input, KeyMod::None, KeyCode::Left);
+        key(&mut input, KeyMod::None, KeyCode::Right);
+        for c in "cd".chars() {
+            key(&mut input, KeyMod::None, KeyCode::Char(c));
+        }
+        key(&mut input, KeyMod::None, KeyCode::Backspace);
+        key(&mut input, KeyMod::None, KeyCode::Backspace);
+        assert_eq!(input.text, "ab");
+
+        key(&mut input, KeyMod::Control, KeyCode::Char('z'));
+        assert_eq!(input.text, "abcd");
+        key(&mut input, KeyMod::Control, KeyCode::Char('z'));
+        assert_eq!(input.text, "ab");
+        key(&mut input, KeyMod::Control, KeyCode::Char('z'));
+        assert_eq!(input.text, "");
+        key(&mut input, KeyMod::Control, KeyCode::Char('y'));
+        key(&mut input, KeyMod::Control, KeyCode::Char('y'));
+        assert_eq!(input.text, "abcd");
+        assert_eq!(input.selection(), None);
     }
 
-    fn apply_style(&mut self) {
+    #[test]
+    fn masks_insert_their_literals_by_graphemes() {
+        let mut input = Input::new("", "", StyleSheet::default()).with_mask("__/e\u{301}_");
+        input.set_size(Size { x: 10, y: 1 });
+        for c in "12".chars() {
+            key(&mut input, KeyMod::None, KeyCode::Char(c));
+        }
+        let row: Vec<String> = input.to_cell_array()[0]
+            .iter()
+            .map(|c| c.symbol.clone())
+            .collect();
+        assert_eq!(row[..5], ["1", "2", "/", "e\u{301}", "_"]);
+
+        for c in "345".chars() {
+            key(&mut input, KeyMod::None, KeyCode::Char(c));
+        }
+        assert_eq!(input.text, "12/e\u{301}3");
+        assert!(input.is_valid());
+        assert_eq!(input.measure(0).x, 6);
+    }
+
+    #[test]
+    fn single_line_scrolls_horizontally() {
+        let mut input = Input::new("", "", StyleSheet::default()).with_single_line();
+        input.set_size(Size { x: 5, y: 1 });
+        for c in "abc".chars() {
+            key(&mut input, KeyMod::None, KeyCode::Char(c));
+        }
+        key(&mut input, KeyMod::None, KeyCode::Enter);
+        for c in "defg".chars() {
+            key(&mut input, KeyMod::None, KeyCode::Char(c));
+        }
+        assert_eq!(input.text, "abcdefg");
+        assert_eq!(input.measure(0), Size { x: 8, y: 1 });
+
+        // `…` marks the text cut off in front of the view
+        let row = |input: &Input| -> String {
+            input.to_cell_array()[0]
+                .iter()
+                .map(|c| c.symbol.as_str())
+                .collect()
+        };
+        assert_eq!(row(&input), "…efg ");
+        key(&mut input, KeyMod::None, KeyCode::Home);
+        assert_eq!(row(&input), "abcd…");
+    }
+
+    fn submit(input: &mut Input, text: &str) {
+        for c in text.chars() {
+            key(input, KeyMod::None, KeyCode::Char(c));
+        }
+        key(input, KeyMod::None, KeyCode::Enter);
+        input.set_text("");
+    }
+
+    #[test]
+    fn up_and_down_browse_the_history_from_the_first_and_last_line() {
+        let mut input = Input::new("", "", StyleSheet::default())
+            .on_submit("submit")
+            .with_history(CommandHistory::new());
+        input.set_size(Size { x: 20, y: 3 });
+        submit(&mut input, "ls");
+        submit(&mut input, "cd");
+
+        input.set_text("a\nbc");
+        input.set_size(Size { x: 20, y: 3 });
+        // up moves to the line above before it browses
+        key(&mut input, KeyMod::None, KeyCode::Up);
+        assert_eq!(input.text, "a\nbc");
+        assert_eq!(input.locate(input.caret()), (0, 1));
+        key(&mut input, KeyMod::None, KeyCode::Up);
+        assert_eq!(input.text, "cd");
+        key(&mut input, KeyMod::None, KeyCode::Up);
+        assert_eq!(input.text, "ls");
+        key(&mut input, KeyMod::None, KeyCode::Down);
+        key(&mut input, KeyMod::None, KeyCode::Down);
+        assert_eq!(input.text, "a\nbc");
+
+        // down moves to the line below before it browses
+        key(&mut input, KeyMod::None, KeyCode::Up);
+        key(&mut input, KeyMod::None, KeyCode::Up);
+        assert_eq!(input.text, "cd");
+        key(&mut input, KeyMod::None, KeyCode::Down);
+        assert_eq!(input.text, "a\nbc");
+        key(&mut input, KeyMod::None, KeyCode::Up);
+        key(&mut input, KeyMod::None, KeyCode::Down);
+        assert_eq!(input.text, "a\nbc");
+        assert_eq!(input.locate(input.caret()), (1, 1));
+    }
+
+    #[test]
+    fn ctrl_r_searches_the_history() {
+        let mut input = Input::new("", "", StyleSheet::default())
+            .on_submit("submit")
+            .with_single_line()
+            .with_history(CommandHistory::new());
+        input.set_size(Size { x: 40, y: 1 });
+        for entry in ["cargo build", "ls", "cargo test"] {
+            submit(&mut input, entry);
+        }
+        key(&mut input, KeyMod::Control, KeyCode::Char('r'));
+        for c in "car".chars() {
+            key(&mut input, KeyMod::None, KeyCode::Char(c));
+        }
+        key(&mut input, KeyMod::Control, KeyCode::Char('r'));
+        key(&mut input, KeyMod::None, KeyCode::End);
+        assert_eq!(input.text, "cargo build");
+
+        // escape leaves the text as it was
+        key(&mut input, KeyMod::Control, KeyCode::Char('r'));
+        key(&mut input, KeyMod::None, KeyCode::Char('l'));
+        key(&mut input, KeyMod::None, KeyCode::Esc);
+        assert_eq!(input.text, "cargo build");
+    }
+
+    #[test]
+    fn suggestions_are_completed_from_the_popup_and_the_ghost_text() {
+        let commands = vec!["checkout", "cherry-pick", "commit"];
+        let mut input = Input::new("", "", StyleSheet::default())
+            .with_single_line()
+            .with_completion(Completion::list(commands));
+        input.set_size(Size { x: 20, y: 1 });
+        input.select(true);
+        key(&mut input, KeyMod::None, KeyCode::Char('c'));
+        key(&mut input, KeyMod::None, KeyCode::Char('h'));
+        assert_eq!(input.get_suggestions(), ["checkout", "cherry-pick"]);
+        assert_eq!(input.ghost_text(), Some("eckout"));
+        assert!(input.get_popup().is_some());
+
+        key(&mut input, KeyMod::None, KeyCode::Down);
+        assert_eq!(input.ghost_text(), Some("erry-pick"));
+        key(&mut input, KeyMod::None, KeyCode::Enter);
+        assert_eq!(input.text, "cherry-pick");
+        assert!(input.get_suggestions().is_empty());
+
+        // the ghost text is accepted with right
+        input.set_text("co");
+        key(&mut input, KeyMod::None, KeyCode::Char('m'));
+        key(&mut input, KeyMod::None, KeyCode::Right);
+        assert_eq!(input.text, "commit");
+        key(&mut input, KeyMod::None, KeyCode::Backspace);
+        key(&mut input, KeyMod::None, KeyCode::Esc);
+        assert!(input.get_suggestions().is_empty());
+    }
+
+    #[test]
+    fn max_shown_of_zero_hides_the_popup() {
+        let completion = Completion::list(vec!["alpha"]).with_max_shown(0);
+        let mut input = Input::new("", "", StyleSheet::default()).with_completion(completion);
+        input.set_size(Size { x: 20, y: 1 });
+        input.select(true);
+        key(&mut input, KeyMod::None, KeyCode::Char('a'));
+        assert_eq!(input.ghost_text(), Some("lpha"));
+        assert!(input.get_popup().is_none());
+    }
+
+    // sends the keys, `\x1b` is escape and `\n` enter
+    fn vi_keys(input: &mut Input, keys: &str) -> Vec<Vec<String>> {
+        let mut messages = Vec::new();
+        for c in keys.chars() {
+            let code = match c {
+                '\x1b' => KeyCode::Esc,
+                '\n' => KeyCode::Enter,
+                '\t' => KeyCode::Tab,
+                c => KeyCode::Char(c),
+            };
+            messages.append(&mut key(input, KeyMod::None, code));
+        }
+        messages
+    }
+
+    fn vi_input() -> Input {
+        let mut input = Input::new("", "", StyleSheet::default()).with_keymap(Keymap::Vi);
+        input.set_size(Size { x: 30, y: 3 });
+        input
+    }
+
+    #[test]
+    fn vi_operators_and_counts_edit_the_text() {
+        let _clipboard = clipboard::lock_for_test();
+        let mut input = vi_input();
+        vi_keys(&mut input, "one two three four\x1b0");
+        assert_eq!(input.vi_mode(), Some(ViMode::Normal));
+
+        vi_keys(&mut input, "dw");
+        assert_eq!(input.text, "two three four");
+        vi_keys(&mut input, "2dw");
+        assert_eq!(input.text, "four");
+        vi_keys(&mut input, "u");
+        assert_eq!(input.text, "two three four");
+
+        vi_keys(&mut input, "wcwTHREE\x1b");
+        assert_eq!(input.text, "two THREE four");
+        vi_keys(&mut input, "$x0");
+        assert_eq!(input.text, "two THREE fou");
+        vi_keys(&mut input, "yyP");
+        assert_eq!(input.text, "two THREE fou\ntwo THREE fou");
+        vi_keys(&mut input, "dd");
+        assert_eq!(input.text, "two THREE fou");
+    }
+
+    #[test]
+    fn vi_change_is_undone_in_one_step() {
+        let mut input = vi_input();
+        vi_keys(&mut input, "abc def\x1b");
+        vi_keys(&mut input, "bcwxyz\x1b");
+        assert_eq!(input.text, "abc xyz");
+        vi_keys(&mut input, "u");
+        assert_eq!(input.text, "abc def");
+        vi_keys(&mut input, "u");
+        assert_eq!(input.text, "");
+    }
+
+    #[test]
+    fn vi_normal_mode_does_not_insert_enter_or_tab() {
+        let mut input = vi_input();
+        vi_keys(&mut input, "abc\x1b\n\tq");
+        assert_eq!(input.text, "abc");
+
+        // enter submits and tab completes when the input has them
+        let mut input = vi_input().on_submit("submit");
+        vi_keys(&mut input, "abc\x1b");
+        assert_eq!(vi_keys(&mut input, "\n"), [["submit"]]);
+        assert_eq!(input.text, "abc");
+
+        let mut input = vi_input().with_completion(Completion::list(vec!["abcdef"]));
+        vi_keys(&mut input, "abc\x1b");
+        input.suggestions.clear();
+        vi_keys(&mut input, "\t");
+        assert_eq!(input.get_suggestions(), ["abcdef"]);
+        assert_eq!(input.text, "abc");
     }
 }
@@ -2,19 +2,47 @@ pub mod button;
 pub mod container;
 pub mod input;
 pub mod text;
+pub mod text_area;
 
+/// default distance between tab stops, see `StyleSheet::tab_width`
 pub const TAB_WIDTH: usize = 4;
 
 use crate::events::Event;
-use crate::{Position, Size};
-use crate::ui::style::{Style, StyleSheet};
+use crate::ui::cell::Canvas;
+use crate::{Position, Rect, Size};
+use crate::ui::style::{State, Style, StyleSheet};
+
+/// shape of the terminal cursor
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum CursorShape {
+    Block,
+    Bar,
+    Underline,
+}
+
+/// terminal cursor requested by a focused widget, `position` is relative to the widget
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Cursor {
+    pub position: Position,
+    pub shape: CursorShape,
+    pub blinking: bool,
+}
+
+/// cells drawn above every other widget, like the suggestions of an input
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Popup {
+    /// area the popup belongs to, relative to the widget. the popup is placed below it,
+    /// or above it if there is no space
+    pub anchor: Rect,
+    pub canvas: Canvas,
+}
 
 pub trait Msg<M> {
     fn to_msg(field: String) -> M;
 }
 
 pub trait Widget {
-    fn to_char_array(&self) -> Vec<Vec<char>>;
+    fn to_cell_array(&self) -> Canvas;
     fn handle_input_event(&mut self, event: Event) -> Vec<Vec<String>>;
 
     fn get_position(&self) -> Position;
@@ -23,8 +51,40 @@ pub trait Widget {
     fn set_position(&mut self, position: Position);
     fn set_size(&mut self, size: Size);
 
+    /// size the content of the widget needs when it is at most `width` cells wide,
+    /// used by parents to resolve `Length::Auto`
+    fn measure(&self, _width: usize) -> Size {
+        self.get_size()
+    }
+
+    /// cells occupied by the widget inside of its parent
+    fn get_rect(&self) -> Rect {
+        Rect::new(self.get_position(), self.get_size())
+    }
+
     fn is_selected(&self) -> bool;
     fn select(&mut self, selected: bool);
+
+    /// where the terminal cursor is shown while the widget is focused
+    fn get_cursor(&self) -> Option<Cursor> {
+        None
+    }
+
+    /// popup shown while the widget is focused
+    fn get_popup(&self) -> Option<Popup> {
+        None
+    }
+
+    /// state used to choose the style overrides of the widget
+    fn get_state(&self) -> State;
+    fn set_hovered(&mut self, hovered: bool);
+    /// disabled widgets ignore input and can not be selected with tab
+    fn set_disabled(&mut self, disabled: bool);
+
+    /// id matched by `#id` selectors of a `Css` stylesheet
+    fn get_id(&self) -> Option<String>;
+    /// classes matched by `.class` selectors of a `Css` stylesheet
+    fn get_classes(&self) -> Vec<String>;
 }
 
 // very dirty but best for end user i think
@@ -35,14 +95,28 @@ pub enum Widgets {
     Button(button::Button),
     Container(container::Container),
     Input(input::Input),
+    TextArea(text_area::TextArea),
+}
+impl Widgets {
+    /// name of the widget matched by type selectors of a `Css` stylesheet
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            Widgets::Text(_) => "Text",
+            Widgets::Button(_) => "Button",
+            Widgets::Container(_) => "Container",
+            Widgets::Input(_) => "Input",
+            Widgets::TextArea(_) => "TextArea",
+        }
+    }
 }
 impl Widget for Widgets{
-    fn to_char_array(&self) -> Vec<Vec<char>> {
+    fn to_cell_array(&self) -> Canvas {
         match self {
-            Widgets::Text(text) => text.to_char_array(),
-            Widgets::Button(button) => button.to_char_array(),
-            Widgets::Container(container) => container.to_char_array(),
-            Widgets::Input(input) => input.to_char_array(),
+            Widgets::Text(text) => text.to_cell_array(),
+            Widgets::Button(button) => button.to_cell_array(),
+            Widgets::Container(container) => container.to_cell_array(),
+            Widgets::Input(input) => input.to_cell_array(),
+            Widgets::TextArea(text_area) => text_area.to_cell_array(),
         }
     }
     fn handle_input_event(&mut self, event: Event) -> Vec<Vec<String>> {
@@ -51,6 +125,17 @@ impl Widget for Widgets{
             Widgets::Button(button) => button.handle_input_event(event),
             Widgets::Container(container) => container.handle_input_event(event),
             Widgets::Input(input) => input.handle_input_event(event),
+            Widgets::TextArea(text_area) => text_area.handle_input_event(event),
+        }
+    }
+
+    fn measure(&self, width: usize) -> Size {
+        match self {
+            Widgets::Text(text) => text.measure(width),
+            Widgets::Button(button) => button.measure(width),
+            Widgets::Container(container) => container.measure(width),
+            Widgets::Input(input) => input.measure(width),
+            Widgets::TextArea(text_area) => text_area.measure(width),
         }
     }
 
@@ -60,6 +145,7 @@ impl Widget for Widgets{
             Widgets::Button(button) => button.get_position(),
             Widgets::Container(container) => container.get_position(),
             Widgets::Input(input) => input.get_position(),
+            Widgets::TextArea(text_area) => text_area.get_position(),
         }
     }
     fn get_size(&self) -> Size {
@@ -68,6 +154,7 @@ impl Widget for Widgets{
             Widgets::Button(button) => button.get_size(),
             Widgets::Container(container) => container.get_size(),
             Widgets::Input(input) => input.get_size(),
+            Widgets::TextArea(text_area) => text_area.get_size(),
         }
     }
 
@@ -77,6 +164,7 @@ impl Widget for Widgets{
             Widgets::Button(button) => button.set_position(position),
             Widgets::Container(container) => container.set_position(position),
             Widgets::Input(input) => input.set_position(position),
+            Widgets::TextArea(text_area) => text_area.set_position(position),
         }
     }
     fn set_size(&mut self, size: Size) {
@@ -85,6 +173,7 @@ impl Widget for Widgets{
             Widgets::Button(button) => button.set_size(size),
             Widgets::Container(container) => container.set_size(size),
             Widgets::Input(input) => input.set_size(size),
+            Widgets::TextArea(text_area) => text_area.set_size(size),
         }
     }
 
@@ -94,6 +183,7 @@ impl Widget for Widgets{
             Widgets::Button(button) => button.is_selected(),
             Widgets::Container(container) => container.is_selected(),
             Widgets::Input(input) => input.is_selected(),
+            Widgets::TextArea(text_area) => text_area.is_selected(),
         }
     }
 
@@ -103,6 +193,77 @@ impl Widget for Widgets{
             Widgets::Button(button) => button.select(selected),
             Widgets::Container(container) => container.select(selected),
             Widgets::Input(input) => input.select(selected),
+            Widgets::TextArea(text_area) => text_area.select(selected),
+        }
+    }
+
+    fn get_cursor(&self) -> Option<Cursor> {
+        match self {
+            Widgets::Text(text) => text.get_cursor(),
+            Widgets::Button(button) => button.get_cursor(),
+            Widgets::Container(container) => container.get_cursor(),
+            Widgets::Input(input) => input.get_cursor(),
+            Widgets::TextArea(text_area) => text_area.get_cursor(),
+        }
+    }
+
+    fn get_popup(&self) -> Option<Popup> {
+        match self {
+            Widgets::Text(text) => text.get_popup(),
+            Widgets::Button(button) => button.get_popup(),
+            Widgets::Container(container) => container.get_popup(),
+            Widgets::Input(input) => input.get_popup(),
+            Widgets::TextArea(text_area) => text_area.get_popup(),
+        }
+    }
+
+    fn get_state(&self) -> State {
+        match self {
+            Widgets::Text(text) => text.get_state(),
+            Widgets::Button(button) => button.get_state(),
+            Widgets::Container(container) => container.get_state(),
+            Widgets::Input(input) => input.get_state(),
+            Widgets::TextArea(text_area) => text_area.get_state(),
+        }
+    }
+
+    fn set_hovered(&mut self, hovered: bool) {
+        match self {
+            Widgets::Text(text) => text.set_hovered(hovered),
+            Widgets::Button(button) => button.set_hovered(hovered),
+            Widgets::Container(container) => container.set_hovered(hovered),
+            Widgets::Input(input) => input.set_hovered(hovered),
+            Widgets::TextArea(text_area) => text_area.set_hovered(hovered),
+        }
+    }
+
+    fn set_disabled(&mut self, disabled: bool) {
+        match self {
+            Widgets::Text(text) => text.set_disabled(disabled),
+            Widgets::Button(button) => button.set_disabled(disabled),
+            Widgets::Container(container) => container.set_disabled(disabled),
+            Widgets::Input(input) => input.set_disabled(disabled),
+            Widgets::TextArea(text_area) => text_area.set_disabled(disabled),
+        }
+    }
+
+    fn get_id(&self) -> Option<String> {
+        match self {
+            Widgets::Text(text) => text.get_id(),
+            Widgets::Button(button) => button.get_id(),
+            Widgets::Container(container) => container.get_id(),
+            Widgets::Input(input) => input.get_id(),
+            Widgets::TextArea(text_area) => text_area.get_id(),
+        }
+    }
+
+    fn get_classes(&self) -> Vec<String> {
+        match self {
+            Widgets::Text(text) => text.get_classes(),
+            Widgets::Button(button) => button.get_classes(),
+            Widgets::Container(container) => container.get_classes(),
+            Widgets::Input(input) => input.get_classes(),
+            Widgets::TextArea(text_area) => text_area.get_classes(),
         }
     }
 }
@@ -114,6 +275,7 @@ impl Style for Widgets {
             Widgets::Button(button) => button.get_style(),
             Widgets::Container(container) => container.get_style(),
             Widgets::Input(input) => input.get_style(),
+            Widgets::TextArea(text_area) => text_area.get_style(),
         }    
     }
 
@@ -123,6 +285,27 @@ impl Style for Widgets {
             Widgets::Button(button) => button.set_style(style),
             Widgets::Container(container) => container.set_style(style),
             Widgets::Input(input) => input.set_style(style),
+            Widgets::TextArea(text_area) => text_area.set_style(style),
+        }
+    }
+
+    fn get_base_style(&self) -> Option<StyleSheet> {
+        match self {
+            Widgets::Text(text) => text.get_base_style(),
+            Widgets::Button(button) => button.get_base_style(),
+            Widgets::Container(container) => container.get_base_style(),
+            Widgets::Input(input) => input.get_base_style(),
+            Widgets::TextArea(text_area) => text_area.get_base_style(),
+        }
+    }
+
+    fn set_base_style(&mut self, style: Option<StyleSheet>) {
+        match self {
+            Widgets::Text(text) => text.set_base_style(style),
+            Widgets::Button(button) => button.set_base_style(style),
+            Widgets::Container(container) => container.set_base_style(style),
+            Widgets::Input(input) => input.set_base_style(style),
+            Widgets::TextArea(text_area) => text_area.set_base_style(style),
         }
     }
 
@@ -132,6 +315,7 @@ impl Style for Widgets {
             Widgets::Button(button) => button.apply_style(),
             Widgets::Container(container) => container.apply_style(),
             Widgets::Input(input) => input.apply_style(),
+            Widgets::TextArea(text_area) => text_area.apply_style(),
         }    
     }
 
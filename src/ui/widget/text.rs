@@ -1,7 +1,9 @@
-use super::{Widget, TAB_WIDTH};
+use super::Widget;
 use crate::events::{Event, MouseEvent, MouseEventKind};
-use crate::ui::style::{Style, StyleSheet};
-use crate::{Position, Size};
+use crate::ui::style::{State, Style, StyleSheet};
+use crate::ui::cell::{styled_canvas, Canvas};
+use crate::ui::text_layout;
+use crate::{Position, Rect, Size};
 
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct Text{
@@ -11,35 +13,43 @@ pub struct Text{
     pub size: Size,
 
     pub style: StyleSheet,
+    // style from before a stylesheet was applied, stylesheets cascade from it
+    base_style: Option<StyleSheet>,
 
     pub onclick_message: Option<String>,
     pub selected: bool,
+    pub disabled: bool,
+    hovered: bool,
+
+    pub id: Option<String>,
+    pub classes: Vec<String>,
 }
 
 impl Text {
     pub fn new<S: Into<String>>(text: S, style: StyleSheet) -> Self {
         let text: String = text.into();
         let height = text.matches('\n').count() + 1;
-        let width = text
-            .split('\n')
-            .into_iter()
-            .map(|x| x.len())
-            .max()
-            .unwrap_or(0);
+        let width = text_layout::text_width(&text, style.tab_width);
 
         Self {
-            text: String::from(text),
+            text,
 
-            position: Position { x: 0.0, y: 0.0 },
+            position: Position::default(),
             size: Size {
-                x: width as f32,
-                y: height as f32,
+                x: width,
+                y: height,
             },
 
             style,
+            base_style: None,
 
             onclick_message: None,
             selected: false,
+            disabled: false,
+            hovered: false,
+
+            id: None,
+            classes: Vec::new(),
         }
     }
 
@@ -48,18 +58,23 @@ impl Text {
         self
     }
 
+    pub fn with_id<S: Into<String>>(mut self, id: S) -> Self {
+        self.id = Some(id.into());
+        self
+    }
+
+    pub fn with_class<S: Into<String>>(mut self, class: S) -> Self {
+        self.classes.push(class.into());
+        self
+    }
+
     pub fn set_text(&mut self, text: &str) {
         let height = text.matches('\n').count() + 1;
-        let width = text
-            .split('\n')
-            .into_iter()
-            .map(|x| x.len())
-            .max()
-            .unwrap_or(0);
+        let width = text_layout::text_width(text, self.style.tab_width);
 
         self.size = Size {
-            x: width as f32,
-            y: height as f32,
+            x: width,
+            y: height,
         };
         self.text = String::from(text);
     }
@@ -70,56 +85,48 @@ impl Text {
 }
 
 impl Widget for Text {
-    fn to_char_array(&self) -> Vec<Vec<char>> {
-        let mut buffer: Vec<Vec<char>> =
-            vec![vec![' '; self.size.x as usize]; self.size.y as usize];
-
-        let chars: Vec<char> = self.text.chars().collect();
-
-        let mut x: usize = 0;
-        let mut y: usize = 0;
-        for char in chars {
-            match char {
-                '\n' => {
-                    y += 1;
-                    x = 0;
-                }
-                '\t' => {
-                    x += TAB_WIDTH;
-                }
-                char => {
-                    if (x as f32) < self.size.x.floor() && (y as f32) < self.size.y.floor() {
-                        buffer[y][x] = char;
-                    }
-                    x += 1;
-                }
-            }
-        }
+    fn to_cell_array(&self) -> Canvas {
+        let style = self.style.for_state(self.get_state());
+        let mut buffer: Canvas = styled_canvas(self.size, &style);
+
+        // text is drawn inside of the padding
+        let area = Rect::new(Position::default(), self.size).inset(style.padding);
+        text_layout::draw_text(&mut buffer, area, &self.text, &style);
 
         buffer
     }
 
     fn handle_input_event(&mut self, event: Event) -> Vec<Vec<String>> {
-        match event {
-            Event::Mouse(event) => match event {
-                MouseEvent { kind, .. } => {
-                    match kind {
-                        MouseEventKind::Down(_) => {
-                            if let Some(message) = self.onclick_message.clone() {
-                                return vec![vec![String::from(message)]];
-                            }
-                            // DEBUG !!!
-                            self.set_text("clicked");
-                        }
-                        _ => (),
-                    }
-                }
-            },
-            _ => (),
+        if self.disabled {
+            return Vec::new();
+        }
+        if let Event::Mouse(MouseEvent {
+            kind: MouseEventKind::Down(_),
+            ..
+        }) = event
+        {
+            if let Some(message) = self.onclick_message.clone() {
+                return vec![vec![message]];
+            }
+            // DEBUG !!!
+            self.set_text("clicked");
         }
         Vec::new()
     }
 
+    fn measure(&self, width: usize) -> Size {
+        let padding = self.style.padding;
+        let text = text_layout::measure(
+            &self.text,
+            width.saturating_sub(padding.horizontal()),
+            &self.style,
+        );
+        Size {
+            x: text.x + padding.horizontal(),
+            y: text.y + padding.vertical(),
+        }
+    }
+
     fn get_position(&self) -> Position {
         self.position
     }
@@ -143,6 +150,32 @@ impl Widget for Text {
     fn select(&mut self, selected: bool) {
         self.selected = selected
     }
+
+    fn get_state(&self) -> State {
+        State {
+            focused: self.selected,
+            hovered: self.hovered,
+            pressed: false,
+            invalid: false,
+            disabled: self.disabled,
+        }
+    }
+
+    fn set_hovered(&mut self, hovered: bool) {
+        self.hovered = hovered;
+    }
+
+    fn set_disabled(&mut self, disabled: bool) {
+        self.disabled = disabled;
+    }
+
+    fn get_id(&self) -> Option<String> {
+        self.id.clone()
+    }
+
+    fn get_classes(&self) -> Vec<String> {
+        self.classes.clone()
+    }
 }
 
 impl Style for Text {
@@ -152,6 +185,15 @@ impl Style for Text {
 
     fn set_style(&mut self, style: StyleSheet) {
         self.style = style;
+        self.base_style = None;
+    }
+
+    fn get_base_style(&self) -> Option<StyleSheet> {
+        self.base_style
+    }
+
+    fn set_base_style(&mut self, style: Option<StyleSheet>) {
+        self.base_style = style;
     }
 
     fn apply_style(&mut self) {
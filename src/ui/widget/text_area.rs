@@ -0,0 +1,866 @@
+use super::container::SCROLL_STEP;
+use super::{Cursor, CursorShape, Widget};
+use crate::clipboard;
+use crate::events::{Event, KeyCode, KeyMod, MouseEvent, MouseEventKind};
+use crate::ui::cell::{paint, styled_canvas, Canvas};
+use crate::ui::history::{Edit, History};
+use crate::ui::keymap::{self, Action, Keymap, Vi, ViMode};
+use crate::ui::style::{Color, State, Style, StyleSheet};
+use crate::ui::text_layout;
+use crate::ui::theme::Role;
+use crate::{Position, Rect, Size};
+use std::ops::Range;
+use unicode_segmentation::UnicodeSegmentation;
+
+/// multi-line text editor with a movable cursor and a scrolling viewport
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TextArea {
+    pub position: Position,
+    pub size: Size,
+
+    pub style: StyleSheet,
+    // style from before a stylesheet was applied, stylesheets cascade from it
+    base_style: Option<StyleSheet>,
+
+    lines: Vec<String>,
+    // line of the cursor and the grapheme in front of which it is
+    line: usize,
+    column: usize,
+    // display column kept while moving over shorter lines
+    target_x: Option<usize>,
+    // lines and cursor before each step
+    history: History<(Vec<String>, usize, usize)>,
+    /// first shown line and display column
+    pub scroll: Position,
+
+    pub line_numbers: bool,
+    pub block_tab: bool,
+    /// keymap of the widget, the global keymap is used when not set
+    pub keymap: Option<Keymap>,
+    vi: Vi,
+    pub cursor_shape: CursorShape,
+    pub cursor_blinking: bool,
+    pub on_change: String,
+
+    pub selected: bool,
+    pub disabled: bool,
+    hovered: bool,
+
+    pub id: Option<String>,
+    pub classes: Vec<String>,
+}
+impl TextArea {
+    pub fn new<S: Into<String>>(message: S, style: StyleSheet) -> Self {
+        Self {
+            position: Position::default(),
+            size: Size::default(),
+
+            style,
+            base_style: None,
+
+            lines: vec![String::new()],
+            line: 0,
+            column: 0,
+            target_x: None,
+            history: History::new(),
+            scroll: Position::default(),
+
+            line_numbers: false,
+            block_tab: false,
+            keymap: None,
+            vi: Vi::default(),
+            cursor_shape: CursorShape::Bar,
+            cursor_blinking: true,
+            on_change: message.into(),
+
+            selected: false,
+            disabled: false,
+            hovered: false,
+
+            id: None,
+            classes: Vec::new(),
+        }
+    }
+
+    pub fn with_text(mut self, text: &str) -> Self {
+        self.set_text(text);
+        self
+    }
+
+    pub fn with_line_numbers(mut self) -> Self {
+        self.line_numbers = true;
+        self
+    }
+
+    pub fn with_tab_blocker(mut self, block: bool) -> Self {
+        self.block_tab = block;
+        self
+    }
+
+    pub fn with_keymap(mut self, keymap: Keymap) -> Self {
+        self.keymap = Some(keymap);
+        self
+    }
+
+    pub fn with_cursor_shape(mut self, shape: CursorShape, blinking: bool) -> Self {
+        self.cursor_shape = shape;
+        self.cursor_blinking = blinking;
+        self
+    }
+
+    pub fn with_id<S: Into<String>>(mut self, id: S) -> Self {
+        self.id = Some(id.into());
+        self
+    }
+
+    pub fn with_class<S: Into<String>>(mut self, class: S) -> Self {
+        self.classes.push(class.into());
+        self
+    }
+
+    pub fn get_text(&self) -> String {
+        self.lines.join("\n")
+    }
+
+    /// replaces the text, the cursor stays in place as far as possible
+    pub fn set_text(&mut self, text: &str) {
+        self.lines = text.split('\n').map(String::from).collect();
+        self.set_cursor(self.line, self.column);
+    }
+
+    pub fn get_lines(&self) -> &[String] {
+        &self.lines
+    }
+
+    /// line and grapheme index of the cursor
+    pub fn get_cursor_position(&self) -> (usize, usize) {
+        (self.line, self.column)
+    }
+
+    pub fn set_cursor(&mut self, line: usize, column: usize) {
+        self.line = line.min(self.lines.len() - 1);
+        self.column = column.min(self.line_length(self.line));
+        self.target_x = None;
+        self.scroll_to_cursor();
+    }
+
+    /// goes back to the text before the last step, returns false if there is nothing to undo
+    pub fn undo(&mut self) -> bool {
+        let current = (self.lines.clone(), self.line, self.column);
+        match self.history.undo(current) {
+            Some(snapshot) => self.restore(snapshot),
+            None => false,
+        }
+    }
+
+    pub fn redo(&mut self) -> bool {
+        let current = (self.lines.clone(), self.line, self.column);
+        match self.history.redo(current) {
+            Some(snapshot) => self.restore(snapshot),
+            None => false,
+        }
+    }
+
+    fn restore(&mut self, (lines, line, column): (Vec<String>, usize, usize)) -> bool {
+        self.lines = lines;
+        self.set_cursor(line, column);
+        true
+    }
+
+    fn apply_key(&mut self, modifier: KeyMod, code: KeyCode, edit: &mut Option<Edit>) {
+        let word = modifier == KeyMod::Control;
+        let page = self.text_area().height.max(1) as isize;
+        if !matches!(
+            code,
+            KeyCode::Up | KeyCode::Down | KeyCode::PageUp | KeyCode::PageDown
+        ) {
+            self.target_x = None;
+        }
+
+        match code {
+            KeyCode::Char('z') if modifier == KeyMod::Control => {
+                self.undo();
+                *edit = None;
+            }
+            KeyCode::Char('y') if modifier == KeyMod::Control => {
+                self.redo();
+                *edit = None;
+            }
+            KeyCode::Char(c) if modifier != KeyMod::Control && modifier != KeyMod::Alt => {
+                *edit = Some(Edit::Insert);
+                self.insert(&c.to_string())
+            }
+            KeyCode::Enter => self.insert_line_break(),
+            KeyCode::Tab if !self.block_tab => self.insert("\t"),
+            KeyCode::Backspace => {
+                *edit = Some(Edit::Remove);
+                self.backspace()
+            }
+            KeyCode::Delete => {
+                *edit = Some(Edit::Remove);
+                self.delete()
+            }
+            KeyCode::Left => self.move_left(word),
+            KeyCode::Right => self.move_right(word),
+            KeyCode::Up => self.move_vertical(-1),
+            KeyCode::Down => self.move_vertical(1),
+            KeyCode::PageUp => self.move_vertical(-page),
+            KeyCode::PageDown => self.move_vertical(page),
+            KeyCode::Home if word => self.set_cursor(0, 0),
+            KeyCode::Home => self.column = 0,
+            KeyCode::End if word => self.set_cursor(self.lines.len() - 1, usize::MAX),
+            KeyCode::End => self.column = self.line_length(self.line),
+            _ => (),
+        }
+    }
+
+    fn apply_action(&mut self, action: Action, edit: &mut Option<Edit>) {
+        match action {
+            Action::Key(modifier, code) => self.apply_key(modifier, code, edit),
+            Action::Move(motion) => {
+                self.set_caret(keymap::target(&self.graphemes(), self.caret(), motion));
+            }
+            Action::Cut(motion) => {
+                let graphemes = self.graphemes();
+                let range = keymap::motion_range(&graphemes, self.caret(), motion);
+                if let Some(text) = keymap::copied_text(&graphemes, self.caret(), motion) {
+                    clipboard::copy(&text);
+                    self.replace_range(range, "");
+                }
+            }
+            Action::Copy(motion) => {
+                if let Some(text) = keymap::copied_text(&self.graphemes(), self.caret(), motion) {
+                    clipboard::copy(&text);
+                }
+            }
+            Action::Paste { after, lines } => {
+                let (index, text, caret) = keymap::paste_at(
+                    &self.graphemes(),
+                    self.caret(),
+                    &clipboard::paste(),
+                    after,
+                    lines,
+                );
+                self.replace_range(index..index, &text);
+                self.set_caret(caret);
+            }
+            Action::Undo => {
+                self.undo();
+                *edit = None;
+            }
+            Action::Redo => {
+                self.redo();
+                *edit = None;
+            }
+            // there is nothing to submit or complete
+            Action::Submit | Action::Complete => (),
+        }
+    }
+
+    // graphemes of all lines with line breaks between them
+    fn graphemes(&self) -> Vec<&str> {
+        let mut graphemes = Vec::new();
+        for (i, line) in self.lines.iter().enumerate() {
+            if i > 0 {
+                graphemes.push("\n");
+            }
+            graphemes.extend(line.graphemes(true));
+        }
+        graphemes
+    }
+
+    // index of the cursor in `graphemes`
+    fn caret(&self) -> usize {
+        let lines: usize = self.lines[..self.line]
+            .iter()
+            .map(|line| line.graphemes(true).count() + 1)
+            .sum();
+        lines + self.column
+    }
+
+    fn set_caret(&mut self, mut index: usize) {
+        for line in 0..self.lines.len() {
+            let length = self.line_length(line);
+            if index <= length {
+                self.set_cursor(line, index);
+                return;
+            }
+            index -= length + 1;
+        }
+    }
+
+    // replaces the graphemes in `range` with `text`, the cursor goes behind it
+    fn replace_range(&mut self, range: Range<usize>, text: &str) {
+        let graphemes = self.graphemes();
+        let before = graphemes[..range.start].concat() + text;
+        let after = graphemes[range.end..].concat();
+        self.lines = (before.clone() + &after)
+            .split('\n')
+            .map(String::from)
+            .collect();
+        let column = before
+            .rsplit('\n')
+            .next()
+            .unwrap_or("")
+            .graphemes(true)
+            .count();
+        self.set_cursor(before.matches('\n').count(), column);
+    }
+
+    /// mode of the vi keymap, none if the widget uses another keymap
+    pub fn vi_mode(&self) -> Option<ViMode> {
+        (self.keymap.unwrap_or_else(keymap::global) == Keymap::Vi).then_some(self.vi.mode)
+    }
+
+    fn line_length(&self, line: usize) -> usize {
+        self.lines[line].graphemes(true).count()
+    }
+
+    // byte index of the grapheme at `column` in the line of the cursor
+    fn byte_index(&self, column: usize) -> usize {
+        let line = &self.lines[self.line];
+        line.grapheme_indices(true)
+            .nth(column)
+            .map_or(line.len(), |(i, _)| i)
+    }
+
+    // display column of the grapheme at `column`
+    fn display_x(&self, line: usize, column: usize) -> usize {
+        text_layout::grapheme_cells(&self.lines[line], self.style.tab_width, false)
+            .get(column)
+            .map_or_else(
+                || text_layout::text_width(&self.lines[line], self.style.tab_width),
+                |cell| cell.x,
+            )
+    }
+
+    // index of the grapheme at display column `x`
+    fn column_at(&self, line: usize, x: usize) -> usize {
+        let cells = text_layout::grapheme_cells(&self.lines[line], self.style.tab_width, false);
+        cells
+            .iter()
+            .position(|cell| cell.x + cell.width > x)
+            .unwrap_or(cells.len())
+    }
+
+    // columns taken by the line numbers and the space behind them
+    fn gutter_width(&self) -> usize {
+        if self.line_numbers {
+            self.lines.len().to_string().len() + 1
+        } else {
+            0
+        }
+    }
+
+    // area inside of the padding, right of the line numbers and left of the vi mode indicator
+    fn text_area(&self) -> Rect {
+        let mut area = Rect::new(Position::default(), self.size).inset(self.style.padding);
+        if self.vi_mode().is_some() {
+            area = keymap::split_mode_indicator(area).0;
+        }
+        let gutter = self.gutter_width().min(area.width);
+        Rect {
+            x: area.x + gutter,
+            width: area.width - gutter,
+            ..area
+        }
+    }
+
+    fn scroll_to_cursor(&mut self) {
+        let area = self.text_area();
+        let x = self.display_x(self.line, self.column);
+
+        if self.line < self.scroll.y {
+            self.scroll.y = self.line;
+        } else if area.height > 0 && self.line >= self.scroll.y + area.height {
+            self.scroll.y = self.line + 1 - area.height;
+        }
+        // the cursor takes a cell behind the text
+        if x < self.scroll.x {
+            self.scroll.x = x;
+        } else if area.width > 0 && x >= self.scroll.x + area.width {
+            self.scroll.x = x + 1 - area.width;
+        }
+    }
+
+    fn insert(&mut self, text: &str) {
+        let index = self.byte_index(self.column);
+        let line = &mut self.lines[self.line];
+        line.insert_str(index, text);
+        self.column = line[..index + text.len()].graphemes(true).count();
+    }
+
+    fn insert_line_break(&mut self) {
+        let index = self.byte_index(self.column);
+        let rest = self.lines[self.line].split_off(index);
+        self.lines.insert(self.line + 1, rest);
+        self.line += 1;
+        self.column = 0;
+    }
+
+    fn backspace(&mut self) {
+        if self.column > 0 {
+            let (start, end) = (
+                self.byte_index(self.column - 1),
+                self.byte_index(self.column),
+            );
+            self.lines[self.line].replace_range(start..end, "");
+            self.column -= 1;
+        } else if self.line > 0 {
+            let line = self.lines.remove(self.line);
+            self.line -= 1;
+            self.column = self.line_length(self.line);
+            self.lines[self.line].push_str(&line);
+        }
+    }
+
+    fn delete(&mut self) {
+        if self.column < self.line_length(self.line) {
+            let (start, end) = (
+                self.byte_index(self.column),
+                self.byte_index(self.column + 1),
+            );
+            self.lines[self.line].replace_range(start..end, "");
+        } else if self.line + 1 < self.lines.len() {
+            let line = self.lines.remove(self.line + 1);
+            self.lines[self.line].push_str(&line);
+        }
+    }
+
+    fn move_left(&mut self, word: bool) {
+        if self.column == 0 {
+            if self.line > 0 {
+                self.line -= 1;
+                self.column = self.line_length(self.line);
+            }
+            return;
+        }
+        if !word {
+            self.column -= 1;
+            return;
+        }
+
+        let graphemes: Vec<&str> = self.lines[self.line].graphemes(true).collect();
+        self.column = text_layout::previous_word_start(&graphemes, self.column);
+    }
+
+    fn move_right(&mut self, word: bool) {
+        let length = self.line_length(self.line);
+        if self.column == length {
+            if self.line + 1 < self.lines.len() {
+                self.line += 1;
+                self.column = 0;
+            }
+            return;
+        }
+        if !word {
+            self.column += 1;
+            return;
+        }
+
+        let graphemes: Vec<&str> = self.lines[self.line].graphemes(true).collect();
+        self.column = text_layout::next_word_start(&graphemes, self.column);
+    }
+
+    fn move_vertical(&mut self, lines: isize) {
+        let x = self
+            .target_x
+            .unwrap_or_else(|| self.display_x(self.line, self.column));
+        self.line = self
+            .line
+            .saturating_add_signed(lines)
+            .min(self.lines.len() - 1);
+        self.column = self.column_at(self.line, x);
+        self.target_x = Some(x);
+    }
+}
+
+impl Widget for TextArea {
+    fn to_cell_array(&self) -> Canvas {
+        let style = self.style.for_state(self.get_state());
+        let mut buffer: Canvas = styled_canvas(self.size, &style);
+
+        let area = self.text_area();
+        let gutter = self.gutter_width().min(area.x);
+        let lines = self.lines.iter().enumerate().skip(self.scroll.y);
+        for (i, line) in lines.take(area.height) {
+            let y = area.y + i - self.scroll.y;
+            if gutter > 0 {
+                let number: Vec<String> = format!("{:>1$}", i + 1, gutter - 1)
+                    .graphemes(true)
+                    .map(String::from)
+                    .collect();
+                text_layout::draw_graphemes(&mut buffer[y], area.x - gutter, area.x, &number);
+            }
+
+            let cells = text_layout::grapheme_cells(line, style.tab_width, false);
+            text_layout::draw_cells(
+                &mut buffer[y],
+                &cells,
+                0,
+                self.scroll.x,
+                area.x,
+                area.right(),
+            );
+        }
+
+        let padded = Rect::new(Position::default(), self.size).inset(self.style.padding);
+        if let (Some(mode), (_, Some(indicator))) =
+            (self.vi_mode(), keymap::split_mode_indicator(padded))
+        {
+            keymap::draw_mode_indicator(&mut buffer, indicator, mode, style.foreground.is_some());
+        }
+
+        if gutter > 0 && style.foreground.is_none() {
+            let numbers = Rect {
+                x: area.x - gutter,
+                width: gutter,
+                ..area
+            };
+            paint(
+                &mut buffer,
+                numbers,
+                Some(Color::Role(Role::Placeholder)),
+                None,
+            );
+        }
+
+        buffer
+    }
+
+    fn handle_input_event(&mut self, event: Event) -> Vec<Vec<String>> {
+        if self.disabled {
+            return Vec::new();
+        }
+
+        let (text, line, column) = (self.lines.clone(), self.line, self.column);
+        // set to the kind of edit by the keys, none for undo and redo
+        let mut edit = Some(Edit::Other);
+        match event {
+            Event::Key(modifier, code) => {
+                let actions = match self.keymap.unwrap_or_else(keymap::global) {
+                    Keymap::Default => vec![Action::Key(modifier, code)],
+                    Keymap::Emacs => keymap::emacs(&modifier, &code),
+                    Keymap::Vi => {
+                        // a change is undone in one step, from its command until escape
+                        let mode = self.vi.mode;
+                        let actions = self.vi.handle(&modifier, &code);
+                        match (mode, self.vi.mode) {
+                            (ViMode::Normal, ViMode::Insert) => self.history.begin_change(),
+                            (ViMode::Insert, ViMode::Normal) => self.history.end_change(),
+                            _ => (),
+                        }
+                        actions
+                    }
+                };
+                for action in actions {
+                    self.apply_action(action, &mut edit);
+                }
+                if self.vi_mode() == Some(ViMode::Normal) {
+                    let caret = self.caret();
+                    let clamped = keymap::clamp_normal_cursor(&self.graphemes(), caret);
+                    if clamped != caret {
+                        self.set_caret(clamped);
+                    }
+                }
+                self.scroll_to_cursor();
+            }
+
+            Event::Mouse(MouseEvent {
+                kind, column, row, ..
+            }) => {
+                let area = self.text_area();
+                let (x, y) = (column as usize, row as usize);
+                match kind {
+                    MouseEventKind::Down(_) if area.contains(x, y) => {
+                        let line = (self.scroll.y + y - area.y).min(self.lines.len() - 1);
+                        let column = self.column_at(line, self.scroll.x + x - area.x);
+                        self.set_cursor(line, column);
+                    }
+                    // scrolls the view without moving the cursor
+                    MouseEventKind::ScrollUp => {
+                        self.scroll.y = self.scroll.y.saturating_sub(SCROLL_STEP)
+                    }
+                    MouseEventKind::ScrollDown => {
+                        self.scroll.y = (self.scroll.y + SCROLL_STEP).min(self.lines.len() - 1)
+                    }
+                    _ => (),
+                }
+            }
+            Event::Resize(_) => (),
+        }
+
+        if self.lines == text {
+            // moving the cursor ends the current step
+            if (self.line, self.column) != (line, column) {
+                self.history.break_group();
+            }
+            return Vec::new();
+        }
+        if let Some(edit) = edit {
+            self.history.record((text, line, column), edit);
+        }
+        vec![vec![self.on_change.clone(), self.get_text()]]
+    }
+
+    /// every line and the line numbers, with a cell for the cursor behind the longest line
+    fn measure(&self, _width: usize) -> Size {
+        let width = self
+            .lines
+            .iter()
+            .map(|line| text_layout::text_width(line, self.style.tab_width))
+            .max()
+            .unwrap_or(0);
+        Size {
+            x: self.gutter_width()
+                + width
+                + 1
+                + self.style.padding.horizontal()
+                + self.vi_mode().map_or(0, |_| keymap::MODE_INDICATOR_WIDTH),
+            y: self.lines.len() + self.style.padding.vertical(),
+        }
+    }
+
+    fn get_position(&self) -> Position {
+        self.position
+    }
+
+    fn set_position(&mut self, position: Position) {
+        self.position = position;
+    }
+
+    fn get_size(&self) -> Size {
+        self.size
+    }
+
+    fn set_size(&mut self, size: Size) {
+        self.size = size;
+    }
+
+    fn is_selected(&self) -> bool {
+        self.selected
+    }
+
+    fn select(&mut self, selected: bool) {
+        self.selected = selected
+    }
+
+    fn get_cursor(&self) -> Option<Cursor> {
+        if !self.selected || self.disabled {
+            return None;
+        }
+
+        let area = self.text_area();
+        let x = (area.x + self.display_x(self.line, self.column)).checked_sub(self.scroll.x)?;
+        let y = (area.y + self.line).checked_sub(self.scroll.y)?;
+        if !area.contains(x, y) {
+            return None;
+        }
+        // a block on the grapheme in the normal mode of the vi keymap
+        let shape = match self.vi_mode() {
+            Some(ViMode::Normal) => CursorShape::Block,
+            _ => self.cursor_shape,
+        };
+        Some(Cursor {
+            position: Position { x, y },
+            shape,
+            blinking: self.cursor_blinking,
+        })
+    }
+
+    fn get_state(&self) -> State {
+        State {
+            focused: self.selected,
+            hovered: self.hovered,
+            pressed: false,
+            invalid: false,
+            disabled: self.disabled,
+        }
+    }
+
+    fn set_hovered(&mut self, hovered: bool) {
+        self.hovered = hovered;
+    }
+
+    fn set_disabled(&mut self, disabled: bool) {
+        self.disabled = disabled;
+    }
+
+    fn get_id(&self) -> Option<String> {
+        self.id.clone()
+    }
+
+    fn get_classes(&self) -> Vec<String> {
+        self.classes.clone()
+    }
+}
+
+impl Style for TextArea {
+    fn get_style(&self) -> StyleSheet {
+        self.style
+    }
+
+    fn set_style(&mut self, style: StyleSheet) {
+        self.style = style;
+        self.base_style = None;
+    }
+
+    fn get_base_style(&self) -> Option<StyleSheet> {
+        self.base_style
+    }
+
+    fn set_base_style(&mut self, style: Option<StyleSheet>) {
+        self.base_style = style;
+    }
+
+    fn apply_style(&mut self) {}
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(text_area: &mut TextArea, modifier: KeyMod, code: KeyCode) {
+        text_area.handle_input_event(Event::Key(modifier, code));
+    }
+
+    fn type_text(text_area: &mut TextArea, text: &str) {
+        for c in text.chars() {
+            key(text_area, KeyMod::None, KeyCode::Char(c));
+        }
+    }
+
+    #[test]
+    fn edits_lines() {
+        let mut text_area = TextArea::new("", StyleSheet::default());
+        text_area.set_size(Size { x: 20, y: 5 });
+        type_text(&mut text_area, "hello");
+        key(&mut text_area, KeyMod::None, KeyCode::Left);
+        key(&mut text_area, KeyMod::None, KeyCode::Left);
+        key(&mut text_area, KeyMod::None, KeyCode::Enter);
+        assert_eq!(text_area.get_lines(), ["hel", "lo"]);
+        assert_eq!(text_area.get_cursor_position(), (1, 0));
+
+        key(&mut text_area, KeyMod::None, KeyCode::Backspace);
+        assert_eq!(text_area.get_text(), "hello");
+        assert_eq!(text_area.get_cursor_position(), (0, 3));
+        key(&mut text_area, KeyMod::None, KeyCode::Delete);
+        assert_eq!(text_area.get_text(), "helo");
+
+        key(&mut text_area, KeyMod::None, KeyCode::End);
+        key(&mut text_area, KeyMod::None, KeyCode::Enter);
+        type_text(&mut text_area, "x");
+        key(&mut text_area, KeyMod::None, KeyCode::Home);
+        key(&mut text_area, KeyMod::None, KeyCode::Delete);
+        key(&mut text_area, KeyMod::None, KeyCode::Delete);
+        assert_eq!(text_area.get_text(), "helo\n");
+        key(&mut text_area, KeyMod::None, KeyCode::Backspace);
+        key(&mut text_area, KeyMod::None, KeyCode::Delete);
+        assert_eq!(text_area.get_text(), "helo");
+    }
+
+    #[test]
+    fn moves_by_words_and_lines() {
+        let mut text_area =
+            TextArea::new("", StyleSheet::default()).with_text("foo bar.baz\nab\nsome line");
+        text_area.set_size(Size { x: 20, y: 5 });
+        text_area.set_cursor(0, 0);
+        key(&mut text_area, KeyMod::Control, KeyCode::Right);
+        assert_eq!(text_area.get_cursor_position(), (0, 4));
+        key(&mut text_area, KeyMod::Control, KeyCode::Right);
+        assert_eq!(text_area.get_cursor_position(), (0, 7));
+        key(&mut text_area, KeyMod::Control, KeyCode::Left);
+        assert_eq!(text_area.get_cursor_position(), (0, 4));
+
+        // the column is kept across shorter lines
+        key(&mut text_area, KeyMod::Control, KeyCode::Right);
+        key(&mut text_area, KeyMod::Control, KeyCode::Right);
+        key(&mut text_area, KeyMod::None, KeyCode::Down);
+        assert_eq!(text_area.get_cursor_position(), (1, 2));
+        key(&mut text_area, KeyMod::None, KeyCode::Down);
+        assert_eq!(text_area.get_cursor_position(), (2, 8));
+
+        // at the start of a line left moves to the end of the one above
+        key(&mut text_area, KeyMod::None, KeyCode::Home);
+        key(&mut text_area, KeyMod::None, KeyCode::Left);
+        assert_eq!(text_area.get_cursor_position(), (1, 2));
+        key(&mut text_area, KeyMod::Control, KeyCode::End);
+        assert_eq!(text_area.get_cursor_position(), (2, 9));
+        key(&mut text_area, KeyMod::Control, KeyCode::Home);
+        assert_eq!(text_area.get_cursor_position(), (0, 0));
+    }
+
+    #[test]
+    fn measures_the_longest_line_and_the_line_numbers() {
+        let text_area = TextArea::new("", StyleSheet::default())
+            .with_text("a\tb\nline\n")
+            .with_line_numbers();
+        let gutter = text_area.gutter_width();
+        assert_eq!(
+            text_area.measure(0),
+            Size {
+                x: gutter + 6,
+                y: 3
+            }
+        );
+    }
+
+    #[test]
+    fn line_breaks_are_a_step_of_their_own() {
+        let mut text_area = TextArea::new("", StyleSheet::default());
+        text_area.set_size(Size { x: 20, y: 5 });
+        type_text(&mut text_area, "ab");
+        key(&mut text_area, KeyMod::None, KeyCode::Enter);
+        type_text(&mut text_area, "cd");
+        key(&mut text_area, KeyMod::Control, KeyCode::Char('z'));
+        assert_eq!(text_area.get_text(), "ab\n");
+        key(&mut text_area, KeyMod::Control, KeyCode::Char('z'));
+        assert_eq!(text_area.get_text(), "ab");
+        assert_eq!(text_area.get_cursor_position(), (0, 2));
+        key(&mut text_area, KeyMod::Control, KeyCode::Char('y'));
+        assert_eq!(text_area.get_text(), "ab\n");
+    }
+
+    // sends the keys, `\x1b` is escape and `\n` enter
+    fn vi_keys(text_area: &mut TextArea, keys: &str) {
+        for c in keys.chars() {
+            let code = match c {
+                '\x1b' => KeyCode::Esc,
+                '\n' => KeyCode::Enter,
+                c => KeyCode::Char(c),
+            };
+            key(text_area, KeyMod::None, code);
+        }
+    }
+
+    #[test]
+    fn vi_edits_lines() {
+        let _clipboard = clipboard::lock_for_test();
+        let mut text_area = TextArea::new("", StyleSheet::default())
+            .with_keymap(Keymap::Vi)
+            .with_text("one\ntwo\nthree\nfour");
+        text_area.set_size(Size { x: 20, y: 5 });
+        vi_keys(&mut text_area, "\x1b");
+        text_area.set_cursor(0, 0);
+
+        vi_keys(&mut text_area, "j$");
+        assert_eq!(text_area.get_cursor_position(), (1, 2));
+        vi_keys(&mut text_area, "2dd");
+        assert_eq!(text_area.get_lines(), ["one", "four"]);
+        vi_keys(&mut text_area, "kyyjp");
+        assert_eq!(text_area.get_lines(), ["one", "four", "one"]);
+        assert_eq!(text_area.get_cursor_position(), (2, 0));
+
+        // the normal mode never inserts, enter and tab included
+        vi_keys(&mut text_area, "\nq");
+        key(&mut text_area, KeyMod::None, KeyCode::Tab);
+        assert_eq!(text_area.get_lines(), ["one", "four", "one"]);
+
+        // a change is undone in one step
+        vi_keys(&mut text_area, "cwtwo\nlines\x1b");
+        assert_eq!(text_area.get_lines(), ["one", "four", "two", "lines"]);
+        vi_keys(&mut text_area, "u");
+        assert_eq!(text_area.get_lines(), ["one", "four", "one"]);
+        vi_keys(&mut text_area, "uu");
+        assert_eq!(text_area.get_text(), "one\ntwo\nthree\nfour");
+    }
+}